@@ -40,6 +40,9 @@ export class CancelToken {
                 const responseTxt = await response.text()
                 try {
                     const body = JSON.parse(responseTxt)
+                    if (body.code && body.message && response.status >= 400) {
+                        return { error: body.message, code: body.code, retryable: body.retryable }
+                    }
                     if(!body.error && response.status >= 400) {
                         return { error: `fetch from ${url} returned status code ${response.status}, response: ${responseTxt}` }
                     }
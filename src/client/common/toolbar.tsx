@@ -20,7 +20,7 @@ import ToggleButtonGroup from '@material-ui/lab/ToggleButtonGroup';
 import WbSunnyIcon from '@material-ui/icons/WbSunny';
 import NightsStayIcon from '@material-ui/icons/NightsStay';
 import Icon from '@mdi/react';
-import { mdiRegex, mdiFormatLetterCase } from '@mdi/js';
+import { mdiRegex, mdiFormatLetterCase, mdiCodeBraces } from '@mdi/js';
 import { Url } from './url';
 import { SearchStyle } from '../../shared/search';
 
@@ -269,6 +269,16 @@ export const KafkaToolbar: React.FunctionComponent<Props> = (props) => {
                           <Icon path={mdiRegex} size={1} color={searchButtonColor}/>
                         </Tooltip>
                       </SearchToggleButton>
+                      <SearchToggleButton
+                        selectColor={searchButtonSelectedColor}
+                        hoverColor={searchButtonHoverColor}
+                        aria-label="Use Structured Query"
+                        value="query"
+                      >
+                        <Tooltip title={`Use Structured Query (i.e value.status == "FAILED" && value.amount > 100)`}>
+                          <Icon path={mdiCodeBraces} size={1} color={searchButtonColor}/>
+                        </Tooltip>
+                      </SearchToggleButton>
                     </SearchButtonGroup>
                   </InputAdornment>
                   </>
@@ -0,0 +1,149 @@
+use std::sync::Mutex;
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+use once_cell::sync::OnceCell;
+use rocket::{Request, Response};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::request::{self, FromRequest, Outcome};
+use rocket::serde::Serialize;
+use tracing::error;
+
+use crate::config;
+use crate::auth::guard::User;
+use crate::audit::sinks::create_sink;
+use crate::common::logging;
+
+static SENDER: OnceCell<Mutex<Sender<AuditRecord>>> = OnceCell::new();
+
+#[derive(Serialize)]
+pub struct AuditRecord {
+    pub timestamp: u128,
+    pub request_id: String,
+    pub user: Option<String>,
+    pub method: String,
+    pub route: Option<String>,
+    pub uri: String,
+    pub status: u16,
+    pub topic: Option<String>,
+    pub partition: Option<i32>,
+    pub offset_from: Option<i64>,
+    pub offset_to: Option<i64>,
+    pub search: Option<String>,
+    pub result_count: Option<usize>,
+}
+
+#[derive(Default)]
+struct AuditDetails {
+    offset_from: Option<i64>,
+    offset_to: Option<i64>,
+    result_count: Option<usize>,
+}
+
+// Request guard for routes that add details to the audit record which are only known after running the route
+// (like the number of results).
+#[derive(Default)]
+pub struct Audit {
+    details: Mutex<AuditDetails>,
+}
+
+impl Audit {
+    pub fn set_offsets(&self, from: i64, to: i64) {
+        let mut details = self.details.lock().unwrap();
+        details.offset_from = Some(from);
+        details.offset_to = Some(to);
+    }
+
+    pub fn set_result_count(&self, count: usize) {
+        self.details.lock().unwrap().result_count = Some(count);
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for &'r Audit {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        Outcome::Success(request.local_cache(|| Audit::default()))
+    }
+}
+
+// Starts the thread writing the audit records to the configured sink (if the audit log is enabled).
+pub fn start() -> Result<(), String> {
+    if !(*config::SETTINGS).audit.enabled {
+        return Ok(());
+    }
+    let mut sink = create_sink()?;
+    let (sender, receiver) = channel::<AuditRecord>();
+    SENDER.set(Mutex::new(sender)).map_err(|_| "audit log already started".to_string())?;
+    thread::spawn(move || {
+        for record in receiver {
+            let line = match serde_json::to_string(&record) {
+                Ok(line) => line,
+                Err(e) => {
+                    error!("Failed serializing audit record: {}", e);
+                    continue;
+                }
+            };
+            let user = record.user.as_deref().unwrap_or("");
+            if let Err(e) = sink.write(user, &line) {
+                error!("Failed writing audit record: {}, record: {}", e, line);
+            }
+        }
+    });
+    Ok(())
+}
+
+// Records every api call once its response is ready. The topic, partition, offsets and search are taken from the
+// route parameters.
+pub struct Auditor;
+
+#[rocket::async_trait]
+impl Fairing for Auditor {
+    fn info(&self) -> Info {
+        Info {
+            name: "Audit log",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let sender = match SENDER.get() {
+            None => return,
+            Some(sender) => sender,
+        };
+        if !request.uri().path().starts_with("/api/") {
+            return;
+        }
+        let route = request.route().map(|r| r.uri.path().to_string());
+        let user = request.local_cache(|| None::<User>).as_ref().map(|u| u.name.to_string());
+        let details = request.local_cache(|| Audit::default()).details.lock().unwrap();
+        let offset = request.query_value::<i64>("offset").and_then(|v| v.ok());
+        let limit = request.query_value::<i64>("limit").and_then(|v| v.ok());
+        let record = AuditRecord {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0),
+            request_id: logging::request_id(request).to_string(),
+            user,
+            method: request.method().to_string(),
+            uri: request.uri().to_string(),
+            status: response.status().code,
+            topic: route_param(request, &route, "topic"),
+            partition: route_param(request, &route, "partition").and_then(|p| p.parse().ok()),
+            offset_from: details.offset_from.or(offset),
+            offset_to: details.offset_to.or(offset.zip(limit).map(|(o, l)| o + l - 1)),
+            search: request.query_value::<String>("search").and_then(|v| v.ok()),
+            result_count: details.result_count,
+            route,
+        };
+        if let Err(e) = sender.lock().unwrap().send(record) {
+            error!("Failed sending audit record: {}", e);
+        }
+    }
+}
+
+// finds the value of a dynamic segment (i.e <topic>) of the route in the request path.
+fn route_param(request: &Request, route: &Option<String>, name: &str) -> Option<String> {
+    let segment = format!("<{}>", name);
+    let index = route.as_ref()?.split('/').filter(|s| !s.is_empty()).position(|s| s == segment)?;
+    request.routed_segment(index).map(|s| s.to_string())
+}
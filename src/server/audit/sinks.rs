@@ -0,0 +1,107 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::time::Duration;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{BaseProducer, BaseRecord, Producer};
+
+use crate::config;
+use crate::common::errors::{map_error, retry};
+
+pub trait Sink {
+    fn write(&mut self, user: &str, line: &str) -> Result<(), String>;
+}
+
+pub fn create_sink() -> Result<Box<dyn Sink>, String> {
+    let audit = &(*config::SETTINGS).audit;
+    match audit.sink.as_str() {
+        "file" => Ok(Box::new(FileSink::new(&audit.file)?)),
+        "kafka" => Ok(Box::new(KafkaSink::new(&audit.kafka)?)),
+        sink => Err(format!("unknown audit sink: {}", sink)),
+    }
+}
+
+// Appends the records to a file. When the file grows over the max size it is renamed to <path>.1 (and <path>.1 to <path>.2, etc),
+// keeping at most max-files old files.
+pub struct FileSink {
+    path: String,
+    max_size: u64,
+    max_files: u32,
+    file: File,
+    size: u64,
+}
+
+impl FileSink {
+    fn new(settings: &config::AuditFile) -> Result<FileSink, String> {
+        let file = open_append(&settings.path)?;
+        let size = map_error(file.metadata())?.len();
+        Ok(FileSink{
+            path: settings.path.to_string(),
+            max_size: settings.max_size_mb * 1024 * 1024,
+            max_files: settings.max_files,
+            file,
+            size,
+        })
+    }
+
+    fn rotate(&mut self) -> Result<(), String> {
+        if self.max_files == 0 {
+            map_error(self.file.set_len(0))?;
+        } else {
+            let _ = fs::remove_file(format!("{}.{}", self.path, self.max_files));
+            for i in (1..self.max_files).rev() {
+                let _ = fs::rename(format!("{}.{}", self.path, i), format!("{}.{}", self.path, i + 1));
+            }
+            map_error(fs::rename(&self.path, format!("{}.1", self.path)))?;
+        }
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &str) -> Result<File, String> {
+    map_error(OpenOptions::new().create(true).append(true).open(path))
+}
+
+impl Sink for FileSink {
+    fn write(&mut self, _user: &str, line: &str) -> Result<(), String> {
+        let len = line.len() as u64 + 1;
+        if self.size > 0 && self.size + len > self.max_size {
+            self.rotate()?;
+        }
+        map_error(writeln!(self.file, "{}", line))?;
+        self.size += len;
+        Ok(())
+    }
+}
+
+// Produces the records to a kafka topic, keyed by the user.
+pub struct KafkaSink {
+    topic: String,
+    producer: BaseProducer,
+}
+
+impl KafkaSink {
+    fn new(settings: &config::AuditKafka) -> Result<KafkaSink, String> {
+        let producer: BaseProducer = retry("creating audit producer", &mut || ClientConfig::new()
+            .set("bootstrap.servers", &(*config::SETTINGS).kafka.urls)
+            .create())?;
+        Ok(KafkaSink{topic: settings.topic.to_string(), producer})
+    }
+}
+
+impl Sink for KafkaSink {
+    fn write(&mut self, user: &str, line: &str) -> Result<(), String> {
+        if let Err((e, _)) = self.producer.send(BaseRecord::to(&self.topic).key(user).payload(line)) {
+            return Err(format!("{:?}", e));
+        }
+        self.producer.poll(Duration::from_millis(0));
+        Ok(())
+    }
+}
+
+impl Drop for KafkaSink {
+    fn drop(&mut self) {
+        self.producer.flush(Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,67 @@
+use rocket::http::{Cookie, CookieJar, Header, SameSite};
+use rocket::request::Request;
+use rocket::response::Redirect;
+use rocket::serde::json::Json;
+
+use crate::config;
+use crate::auth::dto;
+use crate::common::errors::ErrorResult;
+use crate::auth::guard::{User, SESSION_COOKIE};
+use crate::auth::oidc;
+
+#[derive(Responder)]
+pub enum Unauthorized {
+    #[response(status = 401)]
+    Challenge(Json<ErrorResult>, Header<'static>),
+    #[response(status = 401)]
+    Denied(Json<ErrorResult>),
+    Login(Redirect),
+}
+
+#[catch(401)]
+pub fn unauthorized(request: &Request) -> Unauthorized {
+    let mode = (*config::SETTINGS).auth.mode.as_str();
+    let is_api = request.uri().path().as_str().starts_with("/api/");
+    if mode == "oidc" && !is_api {
+        return Unauthorized::Login(Redirect::to("/auth/login"));
+    }
+    let body = Json(ErrorResult{code: "unauthorized", message: "authentication required".to_string(), retryable: false});
+    if mode == "basic" {
+        return Unauthorized::Challenge(body, Header::new("WWW-Authenticate", "Basic realm=\"krowser\", charset=\"UTF-8\""));
+    }
+    Unauthorized::Denied(body)
+}
+
+#[get("/api/auth/me")]
+pub fn get_me(user: User) -> Json<dto::GetMeResult> {
+    let roles = user.role_names();
+    Json(dto::GetMeResult{user: user, mode: (*config::SETTINGS).auth.mode.to_string(), roles: roles})
+}
+
+#[get("/auth/login")]
+pub async fn login() -> Result<Redirect, String> {
+    let url = oidc::login_url().await?;
+    Ok(Redirect::to(url))
+}
+
+#[get("/auth/callback?<code>&<state>")]
+pub async fn callback(code: &str, state: &str, cookies: &CookieJar<'_>) -> Result<Redirect, String> {
+    let session_id = oidc::complete_login(code, state).await?;
+    let secure = (*config::SETTINGS).auth.oidc.redirect_url.starts_with("https://");
+    cookies.add(Cookie::build(SESSION_COOKIE, session_id)
+        .path("/")
+        .http_only(true)
+        .secure(secure)
+        .same_site(SameSite::Lax)
+        .finish());
+    Ok(Redirect::to("/"))
+}
+
+#[get("/auth/logout")]
+pub fn logout(cookies: &CookieJar<'_>) -> Redirect {
+    if let Some(cookie) = cookies.get(SESSION_COOKIE) {
+        oidc::end_session(cookie.value());
+    }
+    cookies.remove(Cookie::named(SESSION_COOKIE));
+    Redirect::to("/")
+}
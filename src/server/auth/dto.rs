@@ -0,0 +1,10 @@
+use rocket::serde::Serialize;
+
+use crate::auth::guard::User;
+
+#[derive(Serialize)]
+pub struct GetMeResult {
+    pub user: User,
+    pub mode: String,
+    pub roles: Vec<String>,
+}
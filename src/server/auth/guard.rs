@@ -0,0 +1,127 @@
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use rocket::http::Status;
+use rocket::request::{self, FromRequest, Outcome, Request};
+use rocket::serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::config;
+use crate::auth::oidc;
+
+pub const SESSION_COOKIE: &str = "krowser_session";
+
+// bcrypt is slow by design, so successful basic auth verifications are remembered for a while (keyed by a digest of
+// the credentials, the passwords themselves are not kept).
+const VERIFIED_TTL: Duration = Duration::from_secs(5 * 60);
+
+static VERIFIED: Lazy<DashMap<Vec<u8>, Instant>> = Lazy::new(DashMap::new);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct User {
+    pub name: String,
+    pub groups: Vec<String>,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for User {
+    type Error = String;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        // the result is cached on the request so it can also be read after the route has run.
+        match request.local_cache(|| authenticate(request)) {
+            Some(user) => Outcome::Success(user.clone()),
+            None => Outcome::Failure((Status::Unauthorized, "authentication required".to_string())),
+        }
+    }
+}
+
+pub fn check_config() -> Result<(), String> {
+    let auth = &(*config::SETTINGS).auth;
+    match auth.mode.as_str() {
+        "none" => Ok(()),
+        "basic" => match &auth.users {
+            Some(users) if !users.is_empty() => {
+                for user in users {
+                    // verifying fails (rather than not matching) when the hash is malformed.
+                    bcrypt::verify("", &user.password_hash).map_err(|e| format!("bad password hash of user {}: {}", user.username, e))?;
+                }
+                Ok(())
+            },
+            _ => Err("auth mode is basic but no users are configured".to_string()),
+        },
+        "header" => match auth.user_header.is_empty() {
+            true => Err("auth mode is header but no user header is configured".to_string()),
+            false => Ok(()),
+        },
+        "oidc" => match auth.oidc.issuer_url.is_empty() || auth.oidc.client_id.is_empty() || auth.oidc.redirect_url.is_empty() {
+            true => Err("auth mode is oidc but the issuer url, client id or redirect url are not configured".to_string()),
+            false => Ok(()),
+        },
+        mode => Err(format!("unknown auth mode: {}", mode)),
+    }
+}
+
+fn authenticate(request: &Request) -> Option<User> {
+    match (*config::SETTINGS).auth.mode.as_str() {
+        "none" => Some(User{name: "anonymous".to_string(), groups: vec![]}),
+        "basic" => authenticate_basic(request),
+        "header" => authenticate_header(request),
+        "oidc" => request.cookies().get(SESSION_COOKIE).and_then(|cookie| oidc::get_session_user(cookie.value())),
+        _ => None,
+    }
+}
+
+fn authenticate_basic(request: &Request) -> Option<User> {
+    let header = request.headers().get_one("Authorization")?;
+    let encoded = header.strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(base64::decode(encoded.trim()).ok()?).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+    let users = (*config::SETTINGS).auth.users.as_ref()?;
+    let user = users.iter().find(|u| u.username == username)?;
+    if !verify_password(username, password, &user.password_hash) {
+        return None;
+    }
+    Some(User{name: user.username.to_string(), groups: user.groups.to_vec()})
+}
+
+fn verify_password(username: &str, password: &str, hash: &str) -> bool {
+    let mut digest = Sha256::new();
+    for part in [username, password, hash] {
+        digest.update((part.len() as u64).to_be_bytes());
+        digest.update(part.as_bytes());
+    }
+    let digest = digest.finalize().to_vec();
+    if VERIFIED.get(&digest).map_or(false, |verified| verified.elapsed() < VERIFIED_TTL) {
+        return true;
+    }
+    VERIFIED.retain(|_, verified| verified.elapsed() < VERIFIED_TTL);
+    if !bcrypt::verify(password, hash).unwrap_or(false) {
+        return false;
+    }
+    VERIFIED.insert(digest, Instant::now());
+    true
+}
+
+// the user header is only trusted when coming from one of the configured proxies (if any are configured).
+fn authenticate_header(request: &Request) -> Option<User> {
+    let auth = &(*config::SETTINGS).auth;
+    if !auth.trusted_proxies.is_empty() {
+        let remote = request.remote()?.ip().to_string();
+        if !auth.trusted_proxies.split(',').any(|proxy| proxy.trim() == remote) {
+            return None;
+        }
+    }
+    let name = request.headers().get_one(&auth.user_header)?.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let groups = match auth.groups_header.is_empty() {
+        true => vec![],
+        false => request.headers().get_one(&auth.groups_header)
+            .map(|groups| groups.split(',').map(|g| g.trim().to_string()).filter(|g| !g.is_empty()).collect())
+            .unwrap_or_default(),
+    };
+    Some(User{name: name.to_string(), groups: groups})
+}
@@ -0,0 +1,124 @@
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use rand::Rng;
+use rand::distributions::Alphanumeric;
+use rocket::serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::OnceCell;
+
+use std::time::{Duration, Instant};
+
+use crate::config;
+use crate::auth::guard::User;
+use crate::common::errors::{map_error, retry_async};
+
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(600);
+
+struct Session {
+    user: User,
+    expires: Instant,
+}
+
+#[derive(Deserialize)]
+struct ProviderMetadata {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+static SESSIONS: Lazy<DashMap<String, Session>> = Lazy::new(DashMap::new);
+static PENDING_LOGINS: Lazy<DashMap<String, Instant>> = Lazy::new(DashMap::new);
+static PROVIDER_METADATA: Lazy<OnceCell<ProviderMetadata>> = Lazy::new(OnceCell::new);
+
+pub fn get_session_user(session_id: &str) -> Option<User> {
+    let session = SESSIONS.get(session_id)?;
+    if session.expires < Instant::now() {
+        drop(session);
+        SESSIONS.remove(session_id);
+        return None;
+    }
+    Some(session.user.clone())
+}
+
+pub fn end_session(session_id: &str) {
+    SESSIONS.remove(session_id);
+}
+
+// returns the url of the identity provider's login page, the provider redirects back to the callback with a code once the user logs in.
+pub async fn login_url() -> Result<String, String> {
+    let oidc = &(*config::SETTINGS).auth.oidc;
+    let metadata = provider_metadata().await?;
+    let state = random_token();
+    PENDING_LOGINS.retain(|_, created| created.elapsed() < LOGIN_TIMEOUT);
+    PENDING_LOGINS.insert(state.to_string(), Instant::now());
+    let url = map_error(reqwest::Url::parse_with_params(&metadata.authorization_endpoint, &[
+        ("response_type", "code"),
+        ("client_id", oidc.client_id.as_str()),
+        ("redirect_uri", oidc.redirect_url.as_str()),
+        ("scope", oidc.scopes.as_str()),
+        ("state", state.as_str()),
+    ]))?;
+    Ok(url.to_string())
+}
+
+// exchanges the code for an access token, fetches the user's claims and starts a new session. Returns the session id.
+pub async fn complete_login(code: &str, state: &str) -> Result<String, String> {
+    match PENDING_LOGINS.remove(state) {
+        Some((_, created)) if created.elapsed() < LOGIN_TIMEOUT => {},
+        _ => return Err("unknown or expired login state".to_string()),
+    }
+    let oidc = &(*config::SETTINGS).auth.oidc;
+    let metadata = provider_metadata().await?;
+    let client = reqwest::Client::new();
+
+    // the code can only be used once, so we don't retry here.
+    let resp = map_error(client.post(&metadata.token_endpoint).form(&[
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", oidc.redirect_url.as_str()),
+        ("client_id", oidc.client_id.as_str()),
+        ("client_secret", oidc.client_secret.as_str()),
+    ]).send().await)?;
+    if !resp.status().is_success() {
+        return Err(format!("oidc token endpoint returned {}", resp.status()));
+    }
+    let token = map_error(resp.json::<TokenResponse>().await)?;
+
+    let resp = map_error(client.get(&metadata.userinfo_endpoint).bearer_auth(&token.access_token).send().await)?;
+    if !resp.status().is_success() {
+        return Err(format!("oidc userinfo endpoint returned {}", resp.status()));
+    }
+    let claims = map_error(resp.json::<Value>().await)?;
+    let name = claims.get(&oidc.username_claim).and_then(|v| v.as_str())
+        .or_else(|| claims.get("sub").and_then(|v| v.as_str()))
+        .ok_or("no username found in the oidc claims".to_string())?;
+    let groups = claims.get(&oidc.groups_claim).and_then(|v| v.as_array())
+        .map(|groups| groups.iter().filter_map(|g| g.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default();
+
+    let session_id = random_token();
+    let now = Instant::now();
+    SESSIONS.retain(|_, session| session.expires > now);
+    SESSIONS.insert(session_id.to_string(), Session{
+        user: User{name: name.to_string(), groups: groups},
+        expires: now + Duration::from_secs(oidc.session_ttl_minutes * 60),
+    });
+    Ok(session_id)
+}
+
+async fn provider_metadata() -> Result<&'static ProviderMetadata, String> {
+    PROVIDER_METADATA.get_or_try_init(|| async {
+        let url = format!("{}/.well-known/openid-configuration", (*config::SETTINGS).auth.oidc.issuer_url.trim_end_matches('/'));
+        let resp = retry_async("fetching oidc provider metadata", || reqwest::get(&url)).await?;
+        map_error(resp.json::<ProviderMetadata>().await)
+    }).await
+}
+
+fn random_token() -> String {
+    rand::thread_rng().sample_iter(&Alphanumeric).take(32).map(char::from).collect()
+}
@@ -0,0 +1,94 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::config;
+use crate::auth::guard::User;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    ConnectWrite,
+    Produce,
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::ConnectWrite => "connect-write",
+            Action::Produce => "produce",
+        }
+    }
+}
+
+struct Role {
+    name: String,
+    users: Vec<String>,
+    groups: Vec<String>,
+    topics_allow: Vec<Regex>,
+    topics_deny: Vec<Regex>,
+    groups_allow: Vec<Regex>,
+    groups_deny: Vec<Regex>,
+    actions: Vec<String>,
+}
+
+static ROLES: Lazy<Vec<Role>> = Lazy::new(|| compile_roles().unwrap());
+
+pub fn check_config() -> Result<(), String> {
+    compile_roles().map(|_| ())
+}
+
+fn compile_roles() -> Result<Vec<Role>, String> {
+    let roles = match &(*config::SETTINGS).auth.roles {
+        None => return Ok(vec![]),
+        Some(roles) => roles,
+    };
+    roles.iter().map(|role| Ok(Role{
+        name: role.name.to_string(),
+        users: role.users.to_vec(),
+        groups: role.groups.to_vec(),
+        topics_allow: compile(&role.name, &role.topics_allow)?,
+        topics_deny: compile(&role.name, &role.topics_deny)?,
+        groups_allow: compile(&role.name, &role.groups_allow)?,
+        groups_deny: compile(&role.name, &role.groups_deny)?,
+        actions: role.actions.to_vec(),
+    })).collect()
+}
+
+// the patterns need to match the whole name, so "orders" doesn't allow "orders-pii".
+fn compile(role: &str, patterns: &Vec<String>) -> Result<Vec<Regex>, String> {
+    patterns.iter()
+        .map(|pattern| Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| format!("bad pattern {} in role {}: {}", pattern, role, e)))
+        .collect()
+}
+
+fn is_allowed(name: &str, allow: &Vec<Regex>, deny: &Vec<Regex>) -> bool {
+    allow.iter().any(|r| r.is_match(name)) && !deny.iter().any(|r| r.is_match(name))
+}
+
+// When no roles are configured everyone is allowed to do everything.
+// Otherwise, a user is allowed to access a topic/group (or perform an action) if any of the user's roles allow it.
+impl User {
+    pub fn can_read_topic(&self, topic: &str) -> bool {
+        ROLES.is_empty() || self.roles().any(|role| is_allowed(topic, &role.topics_allow, &role.topics_deny))
+    }
+
+    pub fn can_read_group(&self, group: &str) -> bool {
+        ROLES.is_empty() || self.roles().any(|role| is_allowed(group, &role.groups_allow, &role.groups_deny))
+    }
+
+    pub fn can(&self, action: Action) -> bool {
+        ROLES.is_empty() || self.roles().any(|role| role.actions.iter().any(|a| a == action.as_str()))
+    }
+
+    pub fn role_names(&self) -> Vec<String> {
+        self.roles().map(|role| role.name.to_string()).collect()
+    }
+
+    fn roles(&self) -> impl Iterator<Item = &'static Role> + '_ {
+        let default_roles = &(*config::SETTINGS).auth.default_roles;
+        ROLES.iter().filter(move |role| {
+            default_roles.contains(&role.name) ||
+            role.users.contains(&self.name) ||
+            role.groups.iter().any(|g| self.groups.contains(g))
+        })
+    }
+}
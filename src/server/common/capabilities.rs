@@ -0,0 +1,35 @@
+use rocket::serde::json::Json;
+use rocket::serde::Serialize;
+
+use crate::config;
+use crate::auth::guard::User;
+
+#[derive(Serialize)]
+pub struct Capabilities {
+    pub read_only: bool,
+    pub produce: bool,
+    pub topic_admin: bool,
+    pub group_admin: bool,
+    pub schema_registry: bool,
+    pub kafka_connect: bool,
+}
+
+// The write actions which are enabled, read-only mode disables all of them.
+pub fn capabilities() -> Capabilities {
+    let server = &(*config::SETTINGS).server;
+    let writes = &server.writes;
+    let enabled = |toggle: bool| toggle && !server.read_only;
+    Capabilities {
+        read_only: server.read_only,
+        produce: enabled(writes.produce),
+        topic_admin: enabled(writes.topic_admin),
+        group_admin: enabled(writes.group_admin),
+        schema_registry: enabled(writes.schema_registry),
+        kafka_connect: enabled(writes.kafka_connect),
+    }
+}
+
+#[get("/api/capabilities")]
+pub fn get_capabilities(_user: User) -> Json<Capabilities> {
+    Json(capabilities())
+}
@@ -1,6 +1,112 @@
 use std::time::Duration;
 use std::thread;
 use async_std::task;
+use rocket::serde::Serialize;
+use rocket::serde::json::Json;
+use rocket::http::Status;
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::common::metrics;
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResult {
+    pub code: &'static str,
+    pub message: String,
+    pub retryable: bool,
+}
+
+// The errors returned by the api routes. Each is returned with a matching status code and a json body with
+// a code (so the client can tell the errors apart), a message and whether retrying the request might succeed.
+#[derive(Debug, Clone)]
+pub enum ApiError {
+    KafkaUnavailable(String),
+    TopicNotFound(String),
+    GroupNotFound(String),
+    DecoderNotFound(String),
+    NotFound(String),
+    Registry(String),
+    Connect(String),
+    Timeout(String),
+    BadRequest(String),
+    Forbidden(String),
+    TooManyRequests(String),
+    Internal(String),
+}
+
+impl ApiError {
+    pub fn status(&self) -> Status {
+        match self {
+            ApiError::KafkaUnavailable(_) => Status::ServiceUnavailable,
+            ApiError::TopicNotFound(_) | ApiError::GroupNotFound(_) | ApiError::DecoderNotFound(_) | ApiError::NotFound(_) => Status::NotFound,
+            ApiError::Registry(_) | ApiError::Connect(_) => Status::BadGateway,
+            ApiError::Timeout(_) => Status::GatewayTimeout,
+            ApiError::BadRequest(_) => Status::BadRequest,
+            ApiError::Forbidden(_) => Status::Forbidden,
+            ApiError::TooManyRequests(_) => Status::TooManyRequests,
+            ApiError::Internal(_) => Status::InternalServerError,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::KafkaUnavailable(_) => "kafka-unavailable",
+            ApiError::TopicNotFound(_) => "topic-not-found",
+            ApiError::GroupNotFound(_) => "group-not-found",
+            ApiError::DecoderNotFound(_) => "decoder-not-found",
+            ApiError::NotFound(_) => "not-found",
+            ApiError::Registry(_) => "schema-registry-error",
+            ApiError::Connect(_) => "kafka-connect-error",
+            ApiError::Timeout(_) => "timeout",
+            ApiError::BadRequest(_) => "bad-request",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::TooManyRequests(_) => "too-many-requests",
+            ApiError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            ApiError::KafkaUnavailable(m) | ApiError::TopicNotFound(m) | ApiError::GroupNotFound(m) |
+            ApiError::DecoderNotFound(m) | ApiError::NotFound(m) | ApiError::Registry(m) | ApiError::Connect(m) |
+            ApiError::Timeout(m) | ApiError::BadRequest(m) | ApiError::Forbidden(m) | ApiError::TooManyRequests(m) |
+            ApiError::Internal(m) => m,
+        }
+    }
+
+    pub fn retryable(&self) -> bool {
+        match self {
+            ApiError::KafkaUnavailable(_) | ApiError::Registry(_) | ApiError::Connect(_) | ApiError::Timeout(_) |
+            ApiError::TooManyRequests(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code(), self.message())
+    }
+}
+
+// errors from the internal helpers (which return strings) that weren't classified are internal errors.
+impl From<String> for ApiError {
+    fn from(err: String) -> Self {
+        ApiError::Internal(err)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let status = self.status();
+        let body = Json(ErrorResult{code: self.code(), message: self.message().to_string(), retryable: self.retryable()});
+        Response::build_from(body.respond_to(request)?)
+            .status(status)
+            .ok()
+    }
+}
 
 pub fn map_error<T, E: std::fmt::Debug>(result: Result<T, E>) -> Result<T, String> {
     match result {
@@ -18,9 +124,11 @@ pub fn retry<T, E: std::fmt::Debug>(name: &str, action: &mut dyn std::ops::FnMut
             Ok(val) => { return Ok(val); },
             Err(err) => {
                 if retries <= 0 {
+                    metrics::inc_retry_failure(name);
                     return Err(format!("{:?}", err));
                 }
-                eprintln!("Failed {}, retrying in {} milliseconds, retries left {}", name, wait, retries);
+                metrics::inc_retry(name);
+                warn!("Failed {}, retrying in {} milliseconds, retries left {}: {:?}", name, wait, retries, err);
                 thread::sleep(Duration::from_millis(wait));
                 retries -= 1;
                 wait *= 2;
@@ -39,9 +147,11 @@ pub async fn retry_async<Fut, T, E: std::fmt::Debug>(name: &str, action: impl Fn
             Ok(val) => { return Ok(val); },
             Err(err) => {
                 if retries <= 0 {
+                    metrics::inc_retry_failure(name);
                     return Err(format!("{:?}", err));
                 }
-                eprintln!("Failed {}, retrying in {} milliseconds, retries left {}", name, wait, retries);
+                metrics::inc_retry(name);
+                warn!("Failed {}, retrying in {} milliseconds, retries left {}: {:?}", name, wait, retries, err);
                 task::sleep(Duration::from_millis(wait)).await;
                 retries -= 1;
                 wait *= 2;
@@ -0,0 +1,89 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+use futures::future::{BoxFuture, FutureExt};
+use rocket::http::Status;
+use rocket::response::status;
+use rocket::serde::json::Json;
+use rocket::serde::Serialize;
+use tokio::task::spawn_blocking;
+
+use crate::config;
+use crate::kafka;
+use crate::common::errors::map_error;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize)]
+pub struct DependencyStatus {
+    pub name: String,
+    pub ok: bool,
+    pub latency_millis: u128,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GetReadinessResult {
+    pub ready: bool,
+    pub dependencies: Vec<DependencyStatus>,
+}
+
+#[get("/health/live")]
+pub fn get_liveness() -> &'static str {
+    "OK"
+}
+
+// Ready when all of the dependencies are reachable, returns 503 otherwise (with the status of each dependency either way).
+#[get("/health/ready")]
+pub async fn get_readiness() -> status::Custom<Json<GetReadinessResult>> {
+    let mut checks: Vec<BoxFuture<DependencyStatus>> = vec![
+        check("kafka".to_string(), check_kafka()).boxed(),
+        check("decoders".to_string(), check_decoders()).boxed(),
+    ];
+    let registry_url = &(*config::SETTINGS).confluent_schema_registry.url;
+    if !registry_url.is_empty() {
+        checks.push(check("schema-registry".to_string(), check_url(format!("{}/subjects", registry_url))).boxed());
+    }
+    for cluster in (*config::SETTINGS).kafka_connect.get_clusters() {
+        if !cluster.url.is_empty() {
+            checks.push(check(format!("kafka-connect:{}", cluster.name), check_url(format!("{}/", cluster.url))).boxed());
+        }
+    }
+    let dependencies = futures::future::join_all(checks).await;
+    let ready = dependencies.iter().all(|d| d.ok);
+    let status = if ready { Status::Ok } else { Status::ServiceUnavailable };
+    status::Custom(status, Json(GetReadinessResult{ready, dependencies}))
+}
+
+async fn check(name: String, action: impl Future<Output = Result<(), String>>) -> DependencyStatus {
+    let start = Instant::now();
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, action).await {
+        Err(_) => Err(format!("timed out after {:?}", CHECK_TIMEOUT)),
+        Ok(result) => result,
+    };
+    DependencyStatus {
+        name,
+        ok: result.is_ok(),
+        latency_millis: start.elapsed().as_millis(),
+        error: result.err(),
+    }
+}
+
+async fn check_kafka() -> Result<(), String> {
+    map_error(spawn_blocking(|| kafka::api::check_brokers(CHECK_TIMEOUT)).await)?
+}
+
+async fn check_decoders() -> Result<(), String> {
+    match map_error(spawn_blocking(kafka::api::installed_decoders).await)? {
+        0 => Err("no decoders installed".to_string()),
+        _ => Ok(()),
+    }
+}
+
+async fn check_url(url: String) -> Result<(), String> {
+    let client = map_error(reqwest::Client::builder().timeout(CHECK_TIMEOUT).build())?;
+    let resp = map_error(client.get(&url).send().await)?;
+    match resp.status().is_success() {
+        true => Ok(()),
+        false => Err(format!("{} returned {}", url, resp.status())),
+    }
+}
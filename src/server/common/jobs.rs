@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use rand::{distributions::Alphanumeric, Rng};
+use rocket::serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::config;
+use crate::auth::guard::User;
+use crate::common::errors::ApiError;
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, ToSchema)]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+struct JobState<T> {
+    status: JobStatus,
+    progress: T,
+    error: Option<String>,
+    finished: Option<Instant>,
+}
+
+// the number of running jobs (of all kinds) by user.
+static RUNNING: Lazy<Mutex<HashMap<String, usize>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// A background job (running in a tokio task) which reports its progress and checks whether it was cancelled.
+pub struct Job<T> {
+    pub id: String,
+    pub owner: String,
+    cancelled: AtomicBool,
+    state: Mutex<JobState<T>>,
+}
+
+impl<T: Clone> Job<T> {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.state.lock().unwrap().progress);
+    }
+
+    pub fn finish(&self, result: Result<(), ApiError>) {
+        let mut state = self.state.lock().unwrap();
+        if state.status == JobStatus::Running {
+            release(&self.owner);
+        }
+        state.status = match &result {
+            Err(_) => JobStatus::Failed,
+            Ok(_) if self.is_cancelled() => JobStatus::Cancelled,
+            Ok(_) => JobStatus::Completed,
+        };
+        state.error = result.err().map(|e| e.to_string());
+        state.finished = Some(Instant::now());
+    }
+
+    // the status, progress and error (if failed) of the job.
+    pub fn snapshot(&self) -> (JobStatus, T, Option<String>) {
+        self.view(T::clone)
+    }
+
+    // like snapshot, but only copies the parts of the progress returned by the function (for jobs with large progress states).
+    pub fn view<R>(&self, f: impl FnOnce(&T) -> R) -> (JobStatus, R, Option<String>) {
+        let state = self.state.lock().unwrap();
+        (state.status, f(&state.progress), state.error.clone())
+    }
+
+    fn is_expired(&self, retention: Duration) -> bool {
+        match self.state.lock().unwrap().finished {
+            None => false,
+            Some(finished) => finished.elapsed() > retention,
+        }
+    }
+}
+
+// The jobs of a single kind, finished jobs are kept for the configured retention so their results can still be polled.
+pub struct Jobs<T> {
+    jobs: Mutex<HashMap<String, Arc<Job<T>>>>,
+}
+
+impl<T: Clone> Jobs<T> {
+    pub fn new() -> Jobs<T> {
+        Jobs { jobs: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn start(&self, user: &User, progress: T) -> Result<Arc<Job<T>>, ApiError> {
+        reserve(&user.name)?;
+        let job = Arc::new(Job{
+            id: rand::thread_rng().sample_iter(&Alphanumeric).take(16).map(char::from).collect(),
+            owner: user.name.to_string(),
+            cancelled: AtomicBool::new(false),
+            state: Mutex::new(JobState{status: JobStatus::Running, progress: progress, error: None, finished: None}),
+        });
+        let mut jobs = self.jobs.lock().unwrap();
+        remove_expired(&mut jobs);
+        jobs.insert(job.id.to_string(), Arc::clone(&job));
+        Ok(job)
+    }
+
+    // users can only see (and cancel) their own jobs.
+    pub fn get(&self, id: &str, user: &User) -> Result<Arc<Job<T>>, ApiError> {
+        let mut jobs = self.jobs.lock().unwrap();
+        remove_expired(&mut jobs);
+        match jobs.get(id) {
+            Some(job) if job.owner == user.name => Ok(Arc::clone(job)),
+            _ => Err(ApiError::NotFound(format!("job {} not found", id))),
+        }
+    }
+}
+
+// counts a new running job of the user, unless the user (or everyone together) already runs too many.
+fn reserve(user: &str) -> Result<(), ApiError> {
+    let settings = &(*config::SETTINGS).jobs;
+    let mut running = RUNNING.lock().unwrap();
+    if running.values().sum::<usize>() >= settings.max_running {
+        return Err(ApiError::TooManyRequests(format!("there are already {} running jobs, try again when some of them finish", settings.max_running)));
+    }
+    let count = running.entry(user.to_string()).or_insert(0);
+    if *count >= settings.max_running_per_user {
+        return Err(ApiError::TooManyRequests(format!("you already have {} running jobs, wait for them to finish or cancel some of them", count)));
+    }
+    *count += 1;
+    Ok(())
+}
+
+fn release(user: &str) {
+    let mut running = RUNNING.lock().unwrap();
+    if let Some(count) = running.get_mut(user) {
+        *count -= 1;
+        if *count == 0 {
+            running.remove(user);
+        }
+    }
+}
+
+fn remove_expired<T: Clone>(jobs: &mut HashMap<String, Arc<Job<T>>>) {
+    let retention = Duration::from_secs((*config::SETTINGS).jobs.retention_minutes * 60);
+    jobs.retain(|_, job| !job.is_expired(retention));
+}
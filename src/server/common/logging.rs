@@ -0,0 +1,63 @@
+use rand::{distributions::Alphanumeric, Rng};
+use rocket::{Data, Request, Route};
+use rocket::http::Header;
+use rocket::route::{Handler, Outcome};
+use tracing::{info_span, Instrument};
+use tracing_subscriber::EnvFilter;
+
+use crate::config;
+
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+struct RequestId(String);
+
+fn check_config() -> Result<(), String> {
+    let logging = &(*config::SETTINGS).logging;
+    EnvFilter::try_new(&logging.level).map_err(|e| format!("bad logging level {}: {}", logging.level, e))?;
+    match logging.format.as_str() {
+        "pretty" | "json" => Ok(()),
+        format => Err(format!("unknown logging format: {}", format)),
+    }
+}
+
+pub fn init() {
+    check_config().unwrap();
+    let logging = &(*config::SETTINGS).logging;
+    let builder = tracing_subscriber::fmt().with_env_filter(EnvFilter::new(&logging.level));
+    match logging.format.as_str() {
+        "json" => builder.json().init(),
+        _ => builder.pretty().init(),
+    }
+}
+
+// The id of the request, taken from the request id header (if the request came through a proxy which sets it) or generated.
+pub fn request_id<'r>(request: &'r Request<'_>) -> &'r str {
+    &request.local_cache(|| RequestId(match request.headers().get_one(REQUEST_ID_HEADER) {
+        Some(id) if !id.is_empty() => id.to_string(),
+        _ => rand::thread_rng().sample_iter(&Alphanumeric).take(16).map(char::from).collect(),
+    })).0
+}
+
+// Wraps the handler of a route so everything logged while handling a request is in a span with the request's id.
+#[derive(Clone)]
+struct Traced(Box<dyn Handler>);
+
+#[rocket::async_trait]
+impl Handler for Traced {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let id = request_id(request);
+        let span = info_span!("request", id, method = %request.method(), uri = %request.uri());
+        let mut outcome = self.0.handle(request, data).instrument(span).await;
+        if let Outcome::Success(response) = &mut outcome {
+            response.set_header(Header::new(REQUEST_ID_HEADER, id.to_string()));
+        }
+        outcome
+    }
+}
+
+pub fn traced(routes: Vec<Route>) -> Vec<Route> {
+    routes.into_iter().map(|mut route| {
+        route.handler = Box::new(Traced(route.handler));
+        route
+    }).collect()
+}
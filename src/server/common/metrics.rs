@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use once_cell::sync::Lazy;
+use prometheus::{Encoder, HistogramVec, IntCounterVec, Histogram, TextEncoder};
+use prometheus::{register_histogram_vec, register_int_counter_vec, register_histogram};
+use rocket::{Data, Request, Response};
+use rocket::fairing::{Fairing, Info, Kind};
+
+use crate::kafka;
+
+static REQUEST_DURATION: Lazy<HistogramVec> = Lazy::new(|| register_histogram_vec!(
+    "krowser_request_duration_seconds", "Duration of the http requests by route", &["method", "route", "status"]).unwrap());
+
+static RETRIES: Lazy<IntCounterVec> = Lazy::new(|| register_int_counter_vec!(
+    "krowser_retries_total", "Number of retries of failed actions (connecting to kafka, calling kafka connect, etc) by action", &["action"]).unwrap());
+
+static RETRY_FAILURES: Lazy<IntCounterVec> = Lazy::new(|| register_int_counter_vec!(
+    "krowser_retry_failures_total", "Number of actions which failed after all of their retries by action", &["action"]).unwrap());
+
+static CACHE_HITS: Lazy<IntCounterVec> = Lazy::new(|| register_int_counter_vec!(
+    "krowser_cache_hits_total", "Number of cache hits by cache", &["cache"]).unwrap());
+
+static CACHE_MISSES: Lazy<IntCounterVec> = Lazy::new(|| register_int_counter_vec!(
+    "krowser_cache_misses_total", "Number of cache misses by cache", &["cache"]).unwrap());
+
+// the hits and misses of every cache at the previous scrape.
+static CACHE_STATS: Lazy<Mutex<HashMap<&'static str, (u64, u64)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+static CACHE_REFRESH_DURATION: Lazy<Histogram> = Lazy::new(|| register_histogram!(
+    "krowser_cache_refresh_duration_seconds", "Duration of the cache refresh cycles",
+    vec![0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0]).unwrap());
+
+static DECODER_RESULTS: Lazy<IntCounterVec> = Lazy::new(|| register_int_counter_vec!(
+    "krowser_decoder_results_total", "Number of decoding attempts by decoder and result (success, no-match or error)", &["decoder", "result"]).unwrap());
+
+pub fn inc_retry(action: &str) {
+    RETRIES.with_label_values(&[action]).inc();
+}
+
+pub fn inc_retry_failure(action: &str) {
+    RETRY_FAILURES.with_label_values(&[action]).inc();
+}
+
+pub fn observe_cache_refresh(start: Instant) {
+    CACHE_REFRESH_DURATION.observe(start.elapsed().as_secs_f64());
+}
+
+pub fn inc_decoder_result(decoder: &str, result: &str) {
+    DECODER_RESULTS.with_label_values(&[decoder, result]).inc();
+}
+
+#[get("/metrics")]
+pub fn get_metrics() -> Result<String, String> {
+    // the caches keep their own (cumulative) statistics, so they're read on every scrape and the counters are
+    // increased by what was added since the previous one.
+    {
+        let mut previous = CACHE_STATS.lock().unwrap();
+        for (cache, hits, misses) in kafka::api::cache_stats() {
+            let (previous_hits, previous_misses) = previous.insert(cache, (hits, misses)).unwrap_or((0, 0));
+            CACHE_HITS.with_label_values(&[cache]).inc_by(delta(previous_hits, hits));
+            CACHE_MISSES.with_label_values(&[cache]).inc_by(delta(previous_misses, misses));
+        }
+    }
+    let mut buffer = vec![];
+    TextEncoder::new().encode(&prometheus::gather(), &mut buffer).map_err(|e| format!("{:?}", e))?;
+    String::from_utf8(buffer).map_err(|e| format!("{:?}", e))
+}
+
+// the statistics of a cache start over if it is reset, then everything counted since is new.
+fn delta(previous: u64, current: u64) -> u64 {
+    if current >= previous { current - previous } else { current }
+}
+
+struct RequestStart(Option<Instant>);
+
+// Measures the duration of every request by its (matched) route.
+pub struct RequestTimer;
+
+#[rocket::async_trait]
+impl Fairing for RequestTimer {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request timer",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        request.local_cache(|| RequestStart(Some(Instant::now())));
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if let Some(start) = request.local_cache(|| RequestStart(None)).0 {
+            let route = request.route().map(|r| r.uri.path()).unwrap_or("unmatched");
+            REQUEST_DURATION
+                .with_label_values(&[request.method().as_str(), route, &response.status().code.to_string()])
+                .observe(start.elapsed().as_secs_f64());
+        }
+    }
+}
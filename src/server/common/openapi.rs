@@ -0,0 +1,42 @@
+use rocket::response::content::RawHtml;
+use rocket::serde::json::Json;
+use utoipa::OpenApi;
+
+use crate::auth::guard::User;
+use crate::common::errors::ErrorResult;
+use crate::kafka;
+use crate::kafka_connect;
+use crate::schema_registry;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "krowser", description = "Web UI backend for browsing kafka, schema registry and kafka-connect"),
+    components(schemas(ErrorResult)),
+)]
+struct ApiDoc;
+
+// The specification of all of the api routes (each api module documents its own routes and dtos).
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    doc.merge(kafka::api::ApiDoc::openapi());
+    doc.merge(kafka::export::ApiDoc::openapi());
+    doc.merge(kafka::import::ApiDoc::openapi());
+    doc.merge(kafka::copy::ApiDoc::openapi());
+    doc.merge(kafka::search_job::ApiDoc::openapi());
+    doc.merge(kafka::key_lookup::ApiDoc::openapi());
+    doc.merge(kafka::compacted::ApiDoc::openapi());
+    doc.merge(kafka::message::ApiDoc::openapi());
+    doc.merge(kafka_connect::api::ApiDoc::openapi());
+    doc.merge(schema_registry::api::ApiDoc::openapi());
+    doc
+}
+
+#[get("/api/openapi.json")]
+pub fn get_openapi(_user: User) -> Json<utoipa::openapi::OpenApi> {
+    Json(openapi())
+}
+
+#[get("/docs")]
+pub fn get_docs(_user: User) -> RawHtml<&'static str> {
+    RawHtml(include_str!("docs.html"))
+}
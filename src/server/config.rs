@@ -27,16 +27,173 @@ pub struct ConfluentSchemaRegistry {
     pub url: String,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct KafkaConnectCluster {
+    pub name: String,
+    pub url: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct KafkaConnect {
+    #[serde(default)]
     pub url: String,
+    pub clusters: Option<Vec<KafkaConnectCluster>>, //todo: support reading this from environment variables (see kafka_topics)
+}
+
+impl KafkaConnect {
+    pub fn get_clusters(&self) -> Vec<KafkaConnectCluster> {
+        match &self.clusters {
+            Some(clusters) if !clusters.is_empty() => clusters.to_vec(),
+            _ => vec![KafkaConnectCluster{name: "default".to_string(), url: self.url.to_string()}],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Writes {
+    pub produce: bool,
+    pub topic_admin: bool,
+    pub group_admin: bool,
+    pub schema_registry: bool,
+    pub kafka_connect: bool,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Server {
     pub port: i32,
+    pub read_only: bool,
+    pub max_import_size_mb: u64,
+    pub writes: Writes,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AuthUser {
+    pub username: String,
+    pub password_hash: String,
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Oidc {
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_url: String,
+    pub scopes: String,
+    pub username_claim: String,
+    pub groups_claim: String,
+    pub session_ttl_minutes: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AuthRole {
+    pub name: String,
+    #[serde(default)]
+    pub users: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub topics_allow: Vec<String>,
+    #[serde(default)]
+    pub topics_deny: Vec<String>,
+    #[serde(default)]
+    pub groups_allow: Vec<String>,
+    #[serde(default)]
+    pub groups_deny: Vec<String>,
+    #[serde(default)]
+    pub actions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Auth {
+    pub mode: String,
+    pub users: Option<Vec<AuthUser>>, //todo: support reading this from environment variables (see kafka_topics)
+    pub user_header: String,
+    pub groups_header: String,
+    pub trusted_proxies: String,
+    pub oidc: Oidc,
+    pub roles: Option<Vec<AuthRole>>, //todo: support reading this from environment variables (see kafka_topics)
+    #[serde(default)]
+    pub default_roles: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MaskingRule {
+    pub topics: String,
+    #[serde(default)]
+    pub json_paths: Vec<String>,
+    #[serde(default)]
+    pub regexes: Vec<String>,
+    pub action: String,
+    #[serde(default)]
+    pub exempt_roles: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct Masking {
+    // the secret key of the hmac used by the "hash" action.
+    #[serde(default)]
+    pub hash_key: String,
+    pub rules: Option<Vec<MaskingRule>>, //todo: support reading this from environment variables (see kafka_topics)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AuditFile {
+    pub path: String,
+    pub max_size_mb: u64,
+    pub max_files: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AuditKafka {
+    pub topic: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Audit {
+    pub enabled: bool,
+    pub sink: String,
+    pub file: AuditFile,
+    pub kafka: AuditKafka,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Logging {
+    pub level: String,
+    pub format: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Jobs {
+    pub retention_minutes: u64,
+    pub max_running: usize,
+    pub max_running_per_user: usize,
+    pub search_concurrency: usize,
+    pub search_max_results: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CompactedViews {
+    pub max_views: usize,
+    pub max_keys: usize,
+    pub max_size_mb: usize,
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,6 +203,13 @@ pub struct Settings  {
     pub confluent_schema_registry: ConfluentSchemaRegistry,
     pub kafka_connect: KafkaConnect,
     pub server: Server,
+    pub auth: Auth,
+    #[serde(default)]
+    pub masking: Masking,
+    pub audit: Audit,
+    pub logging: Logging,
+    pub jobs: Jobs,
+    pub compacted_views: CompactedViews,
 }
 
 impl Settings {
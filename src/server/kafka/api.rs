@@ -2,6 +2,7 @@ use rdkafka::message::OwnedMessage;
 use rdkafka::groups::GroupInfo;
 use rdkafka::Message;
 use rocket::serde::json::Json;
+use utoipa::OpenApi;
 
 use rdkafka::admin::ConfigResourceResult;
 use rdkafka::admin::ResourceSpecifier;
@@ -9,9 +10,9 @@ use rdkafka::admin::AdminOptions;
 use rdkafka::client::DefaultClientContext;
 use rdkafka::message::Timestamp;
 use rdkafka::TopicPartitionList;
-use rdkafka::config::ClientConfig;
+use rdkafka::config::{ClientConfig, RDKafkaLogLevel};
 use rdkafka::admin::AdminClient;
-use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::consumer::{BaseConsumer, CommitMode, Consumer};
 use rdkafka::error::KafkaError;
 use rdkafka::ClientContext;
 use rdkafka::consumer::Rebalance;
@@ -25,56 +26,78 @@ use itertools::Itertools;
 use std::io::{Cursor, BufRead};
 use byteorder::{BigEndian, ReadBytesExt};
 use cached::proc_macro::cached;
+use cached::Cached;
 
 use futures::StreamExt;
 
+use std::collections::HashMap;
 use std::time::{Duration,Instant};
 use std::thread;
 use std::thread::sleep;
 
 use tokio::time::timeout;
 
-use regex::Regex;
+use tracing::{debug, info, warn, error};
 
 use crate::config;
+use crate::auth::guard::User;
+use crate::audit::log::Audit;
 use crate::kafka::dto;
-use crate::common::errors::{map_error, retry};
+use crate::kafka::masking::Masker;
+use crate::kafka::search::Search;
+use crate::kafka::projection::Projection;
+use crate::common::errors::{map_error, retry, ApiError, ErrorResult};
+use crate::common::metrics;
 use crate::kafka::decoders::decoders::DECODERS;
 use serverapi::{Decoder, DecodingAttribute, DecodedContents};
 
-struct CustomContext;
+pub struct CustomContext;
 
 impl ClientContext for CustomContext {
 
+    // forwards librdkafka's logs to tracing (instead of the log crate).
+    fn log(&self, level: RDKafkaLogLevel, fac: &str, log_message: &str) {
+        match level {
+            RDKafkaLogLevel::Emerg
+            | RDKafkaLogLevel::Alert
+            | RDKafkaLogLevel::Critical
+            | RDKafkaLogLevel::Error => error!(target: "librdkafka", facility = fac, "{}", log_message),
+            RDKafkaLogLevel::Warning => warn!(target: "librdkafka", facility = fac, "{}", log_message),
+            RDKafkaLogLevel::Notice
+            | RDKafkaLogLevel::Info => info!(target: "librdkafka", facility = fac, "{}", log_message),
+            RDKafkaLogLevel::Debug => debug!(target: "librdkafka", facility = fac, "{}", log_message),
+        }
+    }
+
     fn error(&self, error: KafkaError, reason: &str) {
         match error {
-            KafkaError::PartitionEOF(i) => println!("Partition EOF: {}, reason: {}", i, reason),
-            _ => ()
+            KafkaError::PartitionEOF(i) => debug!("Partition EOF: {}, reason: {}", i, reason),
+            _ => error!("Kafka client error: {}, reason: {}", error, reason),
         }
     }
 }
 
 impl ConsumerContext for CustomContext {
     fn pre_rebalance(&self, rebalance: &Rebalance) {
-        eprintln!("Pre rebalance {:?}", rebalance);
+        debug!("Pre rebalance {:?}", rebalance);
     }
 
     fn post_rebalance(&self, rebalance: &Rebalance) {
-        eprintln!("Post rebalance {:?}", rebalance);
+        debug!("Post rebalance {:?}", rebalance);
     }
 
     fn commit_callback(&self, result: KafkaResult<()>, _offsets: &TopicPartitionList) {
-        eprintln!("Committing offsets: {:?}", result);
+        debug!("Committing offsets: {:?}", result);
     }
 
 }
 
-type LoggingConsumer = StreamConsumer<CustomContext>;
+pub type LoggingConsumer = StreamConsumer<CustomContext>;
 
 fn kafka_retry<C, T, E: std::fmt::Debug>(
     name: &str,
     consumer_creator: &mut dyn std::ops::FnMut() -> std::result::Result<C, E>,
-    action: &mut dyn std::ops::FnMut(C) -> std::result::Result<T, E>) -> Result<T, String> {
+    action: &mut dyn std::ops::FnMut(C) -> std::result::Result<T, E>) -> Result<T, ApiError> {
     let mut retries = 5;
     let mut wait = 500;
     loop {
@@ -86,9 +109,11 @@ fn kafka_retry<C, T, E: std::fmt::Debug>(
                     Ok(val) => { return Ok(val); },
                     Err(err) => {
                         if retries <= 0 {
-                            return Err(format!("{:?}", err));
+                            metrics::inc_retry_failure(name);
+                            return Err(ApiError::KafkaUnavailable(format!("failed {}: {:?}", name, err)));
                         }
-                        eprintln!("Failed connnecting to kafka for {}, retrying in {} milliseconds, retries left {}", name, wait, retries);
+                        metrics::inc_retry(name);
+                        warn!("Failed connnecting to kafka for {}, retrying in {} milliseconds, retries left {}: {:?}", name, wait, retries, err);
                         thread::sleep(Duration::from_millis(wait));
                         retries -= 1;
                         wait *= 2;
@@ -97,9 +122,11 @@ fn kafka_retry<C, T, E: std::fmt::Debug>(
             },
             Err(err) => {
                 if retries <= 0 {
-                    return Err(format!("{:?}", err));
+                    metrics::inc_retry_failure(name);
+                    return Err(ApiError::KafkaUnavailable(format!("failed {}: {:?}", name, err)));
                 }
-                eprintln!("Failed {}, retrying in {} milliseconds, retries left {}", name, wait, retries);
+                metrics::inc_retry(name);
+                warn!("Failed {}, retrying in {} milliseconds, retries left {}: {:?}", name, wait, retries, err);
                 thread::sleep(Duration::from_millis(wait));
                 retries -= 1;
                 wait *= 2;
@@ -123,17 +150,24 @@ fn group_consumer(group: &str) -> KafkaResult<BaseConsumer> {
 
 }
 
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetTopicsResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/topics")]
-pub fn get_topics() -> Result<Json<dto::GetTopicsResult>, String> {
+pub fn get_topics(user: User) -> Result<Json<dto::GetTopicsResult>, ApiError> {
     let metadata = cached_get_metadata()?;
 
     Ok(Json(dto::GetTopicsResult{
-        topics: metadata,
+        topics: metadata.into_iter().filter(|t| user.can_read_topic(&t.name)).collect(),
     }))
 }
 
 #[cached(time=300, result = true)]
-fn cached_get_metadata() -> Result<Vec<dto::TopicMetadata>, String> {
+fn cached_get_metadata() -> Result<Vec<dto::TopicMetadata>, ApiError> {
     let timeout = Duration::from_secs(10);
     let meta = kafka_retry("fetching metadata", &mut base_consumer, &mut |consumer| consumer
         .fetch_metadata(None, timeout))?;
@@ -156,14 +190,23 @@ fn cached_get_metadata() -> Result<Vec<dto::TopicMetadata>, String> {
     )
 }
 
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetTopicResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/topic/<topic>")]
-pub fn get_topic(topic: &str) -> Result<Json<dto::GetTopicResult>, String> {
-    let res = cached_get_topic(topic.to_string())?;
+pub fn get_topic(topic: &str, user: User) -> Result<Json<dto::GetTopicResult>, ApiError> {
+    check_topic_access(&user, topic)?;
+    let mut res = cached_get_topic(topic.to_string())?;
+    res.consumer_groups.retain(|g| user.can_read_group(&g.group_id));
     Ok(Json(res))
 }
 
 #[cached(time=300, size=10000, result = true)]
-fn cached_get_topic(topic: String) -> Result<dto::GetTopicResult, String> {
+fn cached_get_topic(topic: String) -> Result<dto::GetTopicResult, ApiError> {
     let offsets = _get_offsets(&topic)?;
     let groups = _get_topic_consumer_groups(&topic, &offsets, false)?;
     Ok(dto::GetTopicResult{
@@ -172,38 +215,63 @@ fn cached_get_topic(topic: String) -> Result<dto::GetTopicResult, String> {
     })
 }
 
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetTopicConfigsResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/topic/<topic>/config")]
-pub async fn get_topic_configs(topic: &str) -> Result<Json<dto::GetTopicConfigsResult>, String> {
+pub async fn get_topic_configs(topic: &str, user: User) -> Result<Json<dto::GetTopicConfigsResult>, ApiError> {
+    check_topic_access(&user, topic)?;
+    let entries = fetch_topic_configs(topic).await?;
+    Ok(Json(dto::GetTopicConfigsResult{ entries: entries }))
+}
+
+pub async fn fetch_topic_configs(topic: &str) -> Result<Vec<dto::ConfigEntry>, ApiError> {
     let client: AdminClient<DefaultClientContext> = map_error(ClientConfig::new()
-        .set("bootstrap.servers", &(*config::SETTINGS).kafka.urls).create())?;
+        .set("bootstrap.servers", &(*config::SETTINGS).kafka.urls).create()).map_err(ApiError::KafkaUnavailable)?;
 
     let opts = AdminOptions::new().operation_timeout(Some(Duration::from_secs(5)));
     let configs: Vec<ConfigResourceResult> = map_error(client.describe_configs(&[
         ResourceSpecifier::Topic(topic),
-    ], &opts).await)?;
-
-    let entries = _get_entries(configs)?;
+    ], &opts).await).map_err(ApiError::KafkaUnavailable)?;
 
-    Ok(Json(dto::GetTopicConfigsResult{ entries: entries }))
+    _get_entries(configs)
 }
 
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetBrokerConfigsResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/broker/<broker>/config")]
-pub async fn get_broker_configs(broker: i32) -> Result<Json<dto::GetBrokerConfigsResult>, String> {
+pub async fn get_broker_configs(broker: i32, _user: User) -> Result<Json<dto::GetBrokerConfigsResult>, ApiError> {
     let client: AdminClient<DefaultClientContext> = map_error(ClientConfig::new()
-        .set("bootstrap.servers", &(*config::SETTINGS).kafka.urls).create())?;
+        .set("bootstrap.servers", &(*config::SETTINGS).kafka.urls).create()).map_err(ApiError::KafkaUnavailable)?;
 
     let opts = AdminOptions::new().operation_timeout(Some(Duration::from_secs(5)));
     let configs: Vec<ConfigResourceResult> = map_error(client.describe_configs(&[
         ResourceSpecifier::Broker(broker),
-    ], &opts).await)?;
+    ], &opts).await).map_err(ApiError::KafkaUnavailable)?;
 
     let entries = _get_entries(configs)?;
 
     Ok(Json(dto::GetBrokerConfigsResult{ entries: entries }))
 }
 
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetClusterResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/cluster")]
-pub fn get_cluster() -> Result<Json<dto::GetClusterResult>, String> {
+pub fn get_cluster(_user: User) -> Result<Json<dto::GetClusterResult>, ApiError> {
     let timeout = Duration::from_secs(10);
     let metadata = kafka_retry("fetching metadata", &mut base_consumer, &mut |consumer| consumer
         .fetch_metadata(None, timeout))?;
@@ -220,11 +288,21 @@ pub fn get_cluster() -> Result<Json<dto::GetClusterResult>, String> {
     Ok(Json(dto::GetClusterResult{ brokers: brokers }))
 }
 
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetGroupsResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/groups")]
-pub fn get_groups() -> Result<Json<dto::GetGroupsResult>, String> {
+pub fn get_groups(user: User) -> Result<Json<dto::GetGroupsResult>, ApiError> {
     let groups = cached_fetch_group_list()?;
     let mut out = Vec::with_capacity(groups.len());
     for group in groups {
+        if !user.can_read_group(&group.name) {
+            continue;
+        }
         let members = _get_members(&group)?;
         out.push(
             dto::GroupMetadata{
@@ -240,8 +318,18 @@ pub fn get_groups() -> Result<Json<dto::GetGroupsResult>, String> {
     Ok(Json(dto::GetGroupsResult{groups: out}))
 }
 
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetGroupMembersResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/members/<group>")]
-pub fn get_group_members(group: &str) -> Result<Json<dto::GetGroupMembersResult>, String> {
+pub fn get_group_members(group: &str, user: User) -> Result<Json<dto::GetGroupMembersResult>, ApiError> {
+    if !user.can_read_group(group) {
+        return Err(ApiError::Forbidden(format!("user {} is not allowed to access consumer group {}", user.name, group)));
+    }
     let timeout = Duration::from_secs(10);
     let groups = kafka_retry("fetching groups", &mut base_consumer, &mut |consumer| consumer
         .fetch_group_list(Some(group), timeout))?;
@@ -249,10 +337,10 @@ pub fn get_group_members(group: &str) -> Result<Json<dto::GetGroupMembersResult>
     let groups = groups.groups();
 
     if groups.len() == 0 {
-        return Err("no groups found".to_string());
+        return Err(ApiError::GroupNotFound(format!("consumer group {} not found", group)));
     }
     if groups.len() > 1 {
-        return Err("too many groups found".to_string());
+        return Err(ApiError::Internal("too many groups found".to_string()));
     }
     let group = &groups[0];
     let members = _get_members(&to_group(group))?;
@@ -260,8 +348,32 @@ pub fn get_group_members(group: &str) -> Result<Json<dto::GetGroupMembersResult>
     Ok(Json(dto::GetGroupMembersResult{members: members}))
 }
 
+// checks that the brokers are reachable, without retrying so an unreachable cluster is reported quickly.
+pub fn check_brokers(timeout: Duration) -> Result<(), String> {
+    let consumer = map_error(base_consumer())?;
+    let metadata = map_error(consumer.fetch_metadata(None, timeout))?;
+    if metadata.brokers().is_empty() {
+        return Err("no brokers found".to_string());
+    }
+    Ok(())
+}
+
+// the number of installed decoders (loading them if they weren't loaded yet).
+pub fn installed_decoders() -> usize {
+    unsafe {
+        DECODERS.decoders.len()
+    }
+}
+
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetDecodersResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/topic/decoders")]
-pub fn get_decoders() -> Result<Json<dto::GetDecodersResult>, String> {
+pub fn get_decoders(_user: User) -> Result<Json<dto::GetDecodersResult>, ApiError> {
     let mut decoders: Vec<dto::DecoderMetadata>;
     unsafe {
         decoders = DECODERS.get_decoders_metadata();
@@ -270,23 +382,48 @@ pub fn get_decoders() -> Result<Json<dto::GetDecodersResult>, String> {
     Ok(Json(dto::GetDecodersResult{decoders: decoders}))
 }
 
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetTopicConsumerGroupsResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/topic/<topic>/consumer_groups")]
-pub fn get_topic_consumer_groups(topic: &str) -> Result<Json<dto::GetTopicConsumerGroupsResult>, String> {
+pub fn get_topic_consumer_groups(topic: &str, user: User) -> Result<Json<dto::GetTopicConsumerGroupsResult>, ApiError> {
+    check_topic_access(&user, topic)?;
     let offsets = _get_offsets(topic)?;
-    let groups = _get_topic_consumer_groups(topic, &offsets, true)?;
+    let mut groups = _get_topic_consumer_groups(topic, &offsets, true)?;
+    groups.retain(|g| user.can_read_group(&g.group_id));
     Ok(Json(dto::GetTopicConsumerGroupsResult{consumer_groups: groups}))
 }
 
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetTopicOffsetsResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/topic/<topic>/offsets")]
-pub fn get_offsets(topic: &str) -> Result<Json<dto::GetTopicOffsetsResult>, String> {
+pub fn get_offsets(topic: &str, user: User) -> Result<Json<dto::GetTopicOffsetsResult>, ApiError> {
+    check_topic_access(&user, topic)?;
     let offsets = _get_offsets(topic)?;
     Ok(Json(dto::GetTopicOffsetsResult{
         offsets: offsets,
     }))
 }
 
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetOffsetForTimestampResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/offset/<topic>/<partition>/<timestamp>")]
-pub fn get_offset_for_timestamp(topic: &str, partition: i32, timestamp: i64) -> Result<Json<dto::GetOffsetForTimestampResult>, String> {
+pub fn get_offset_for_timestamp(topic: &str, partition: i32, timestamp: i64, user: User) -> Result<Json<dto::GetOffsetForTimestampResult>, ApiError> {
+    check_topic_access(&user, topic)?;
     let timeout = Duration::from_secs(10);
 
     let offsets = kafka_retry("fetching offsets for times", &mut || group_consumer("krowser"), &mut |consumer| {
@@ -302,7 +439,7 @@ pub fn get_offset_for_timestamp(topic: &str, partition: i32, timestamp: i64) ->
         }));
     }
     if partition_offsets.len() > 1 {
-        return Err("found too many offsets".to_string());
+        return Err(ApiError::Internal("found too many offsets".to_string()));
     }
     let partition_offset = &partition_offsets[0];
     match partition_offset.offset() {
@@ -315,10 +452,17 @@ pub fn get_offset_for_timestamp(topic: &str, partition: i32, timestamp: i64) ->
                 offset: partition_offsets.high,
             }))
         },
-        _ => Err(format!("bad offset type: {:?}", partition_offset.offset()))
+        _ => Err(ApiError::Internal(format!("bad offset type: {:?}", partition_offset.offset())))
     }
 }
 
+pub fn check_topic_access(user: &User, topic: &str) -> Result<(), ApiError> {
+    if !user.can_read_topic(topic) {
+        return Err(ApiError::Forbidden(format!("user {} is not allowed to access topic {}", user.name, topic)));
+    }
+    Ok(())
+}
+
 fn _get_members(group: &CachedGroup) -> Result<Vec<dto::GroupMemberMetadata>, String> {
     let mut members = Vec::with_capacity(group.members.len());
     for member in &group.members {
@@ -328,7 +472,7 @@ fn _get_members(group: &CachedGroup) -> Result<Vec<dto::GroupMemberMetadata>, St
                 match _parse_member_assignment(&payload) {
                     Ok(assignments) => assignments.iter().map(|ass| ass.topic.to_string() + " [" + &ass.partitions.iter().join(",") + "]").join(", "),
                     Err(err) => {
-                        eprintln!("failed parsing group {}: {}", group.name.to_string(), err);
+                        warn!("failed parsing group {}: {}", group.name.to_string(), err);
                         "???".to_string()
                     }
                 }
@@ -353,12 +497,12 @@ fn _get_members(group: &CachedGroup) -> Result<Vec<dto::GroupMemberMetadata>, St
     Ok(members)
 }
 
-fn _get_entries(configs: Vec<ConfigResourceResult>) -> Result<Vec<dto::ConfigEntry>, String> {
+fn _get_entries(configs: Vec<ConfigResourceResult>) -> Result<Vec<dto::ConfigEntry>, ApiError> {
     if configs.len() == 0 {
-        return Err("no configs found".to_string());
+        return Err(ApiError::NotFound("no configs found".to_string()));
     }
     if configs.len() > 1 {
-        return Err("too many configs found".to_string());
+        return Err(ApiError::Internal("too many configs found".to_string()));
     }
     let config = map_error(configs[0].as_ref())?;
 
@@ -380,11 +524,11 @@ fn _get_entries(configs: Vec<ConfigResourceResult>) -> Result<Vec<dto::ConfigEnt
     Ok(entries)
 }
 
-fn _get_offsets(topic: &str) -> Result<Vec<dto::TopicOffsets>, String> {
+fn _get_offsets(topic: &str) -> Result<Vec<dto::TopicOffsets>, ApiError> {
     let timeout = Duration::from_secs(10);
     let topics = cached_get_metadata()?;
 
-    let topic_metadata = topics.iter().find(|t| -> bool {t.name == topic}).ok_or("failed to find topic in metadata".to_string())?;
+    let topic_metadata = topics.iter().find(|t| -> bool {t.name == topic}).ok_or(ApiError::TopicNotFound(format!("topic {} not found", topic)))?;
 
     if topic_metadata.partitions.len() == 1 {
         let out = _get_offsets_for_partition(topic, topic_metadata.partitions[0].partition_id)?;
@@ -426,11 +570,11 @@ fn _get_offsets(topic: &str) -> Result<Vec<dto::TopicOffsets>, String> {
     Ok(offsets)
 }
 
-fn _get_offsets_for_partition(topic: &str, partition: i32) -> Result<dto::TopicOffsets, String> {
-    let consumer: BaseConsumer = retry("connecting consumer", &mut || base_consumer())?;
+fn _get_offsets_for_partition(topic: &str, partition: i32) -> Result<dto::TopicOffsets, ApiError> {
+    let consumer: BaseConsumer = retry("connecting consumer", &mut || base_consumer()).map_err(ApiError::KafkaUnavailable)?;
     let timeout = Duration::from_secs(10);
     let watermarks = retry("fetching watermarks", &mut || consumer
-        .fetch_watermarks(topic, partition, timeout))?;
+        .fetch_watermarks(topic, partition, timeout)).map_err(ApiError::KafkaUnavailable)?;
     Ok(dto::TopicOffsets{
         partition: partition,
         low: watermarks.0,
@@ -473,27 +617,25 @@ fn to_group(group: &GroupInfo) -> CachedGroup {
 }
 
 #[cached(time=60, size=10000, result = true)]
-fn cached_fetch_group_list() -> Result<Vec<CachedGroup>, String> {
-    eprintln!("Refreshing groups cache");
+fn cached_fetch_group_list() -> Result<Vec<CachedGroup>, ApiError> {
+    debug!("Refreshing groups cache");
     let start = Instant::now();
 
     let timeout = Duration::from_secs(10);
     let groups = kafka_retry("fetching groups", &mut base_consumer, &mut |consumer| consumer
         .fetch_group_list(None, timeout))?;
     let out = groups.groups().iter().map(|group|to_group(group)).collect();
-    eprintln!("Refreshed groups cache in {:?}", start.elapsed());
+    debug!("Refreshed groups cache in {:?}", start.elapsed());
     Ok(out)
 }
 
-fn _get_topic_consumer_groups(topic: &str, offsets: &Vec<dto::TopicOffsets>, with_committed_offset: bool) -> Result<Vec<dto::TopicConsumerGroup>, String> {
-    let timeout = Duration::from_secs(10);
+fn _get_topic_consumer_groups(topic: &str, offsets: &Vec<dto::TopicOffsets>, with_committed_offset: bool) -> Result<Vec<dto::TopicConsumerGroup>, ApiError> {
     let groups = cached_fetch_group_list()?;
     let mut topic_groups = Vec::with_capacity(groups.len());
     for group in groups {
         if group.protocol_type != "consumer" {
             continue
         }
-        let num_members = group.members.len();
         for member in group.members {
             match member.assignment {
                 None => continue,
@@ -501,32 +643,11 @@ fn _get_topic_consumer_groups(topic: &str, offsets: &Vec<dto::TopicOffsets>, wit
                     let assignments = _parse_member_assignment(&payload)?;
                     for assgn in assignments {
                         if assgn.topic == topic {
-                            let mut consumer_group_offsets = Vec::with_capacity(num_members);
-                            if with_committed_offset {
-                                let committed: TopicPartitionList = kafka_retry("fetching offsets for times", &mut || group_consumer(&group.name), &mut |consumer| {
-                                    let mut tpl = TopicPartitionList::new();
-                                    for offset in offsets {
-                                        tpl.add_partition_offset(topic, offset.partition, rdkafka::Offset::Offset(0))?;
-                                    }
-                                    consumer.committed_offsets(tpl, timeout)
-                                })?;
-                                for elem in committed.elements() {
-                                    if let rdkafka::Offset::Offset(offset) = elem.offset() {
-                                        if let Some(partition_offsets) = offsets.iter().find(|v| v.partition == elem.partition()) {
-                                            let consumer_offsets = dto::ConsumerGroupOffsets{
-                                                metadata: Some(elem.metadata().to_string()),
-                                                offset: offset,
-                                                partition_offsets: *partition_offsets,
-                                            };
-                                            consumer_group_offsets.push(consumer_offsets);
-                                        } else {
-                                            eprintln!("did not find offsets for topic {} and partition {}", topic, elem.partition());
-                                        }
-                                    } else {
-                                        eprintln!("bad offset type: {:?}", elem.offset());
-                                    }
-                                }
-                            }
+                            let consumer_group_offsets = if with_committed_offset {
+                                _get_committed_offsets(&group.name, topic, offsets)?
+                            } else {
+                                vec![]
+                            };
                             let topic_group = dto::TopicConsumerGroup{
                                 group_id: group.name.to_string(),
                                 offsets: consumer_group_offsets,
@@ -542,6 +663,117 @@ fn _get_topic_consumer_groups(topic: &str, offsets: &Vec<dto::TopicOffsets>, wit
     Ok(topic_groups)
 }
 
+fn _get_committed_offsets(group: &str, topic: &str, offsets: &Vec<dto::TopicOffsets>) -> Result<Vec<dto::ConsumerGroupOffsets>, ApiError> {
+    let timeout = Duration::from_secs(10);
+    let committed: TopicPartitionList = kafka_retry("fetching offsets for times", &mut || group_consumer(group), &mut |consumer| {
+        let mut tpl = TopicPartitionList::new();
+        for offset in offsets {
+            tpl.add_partition_offset(topic, offset.partition, rdkafka::Offset::Offset(0))?;
+        }
+        consumer.committed_offsets(tpl, timeout)
+    })?;
+    let mut consumer_group_offsets = Vec::with_capacity(offsets.len());
+    for elem in committed.elements() {
+        if let rdkafka::Offset::Offset(offset) = elem.offset() {
+            if let Some(partition_offsets) = offsets.iter().find(|v| v.partition == elem.partition()) {
+                let consumer_offsets = dto::ConsumerGroupOffsets{
+                    metadata: Some(elem.metadata().to_string()),
+                    offset: offset,
+                    partition_offsets: *partition_offsets,
+                };
+                consumer_group_offsets.push(consumer_offsets);
+            } else {
+                warn!("did not find offsets for topic {} and partition {}", topic, elem.partition());
+            }
+        } else {
+            warn!("bad offset type: {:?}", elem.offset());
+        }
+    }
+    Ok(consumer_group_offsets)
+}
+
+pub fn get_topic_names() -> Result<Vec<String>, ApiError> {
+    let metadata = cached_get_metadata()?;
+    Ok(metadata.into_iter().map(|t| t.name).collect())
+}
+
+pub fn get_topic_partitions(topic: &str) -> Result<Vec<i32>, ApiError> {
+    let metadata = cached_get_metadata()?;
+    let topic_metadata = metadata.iter().find(|t| t.name == topic).ok_or(ApiError::TopicNotFound(format!("topic {} not found", topic)))?;
+    Ok(topic_metadata.partitions.iter().map(|p| p.partition_id).collect())
+}
+
+// returns the topics assigned to the members of the group, or None if the group does not exist.
+pub fn get_group_topics(group: &str) -> Result<Option<Vec<String>>, ApiError> {
+    let groups = cached_fetch_group_list()?;
+    let group = match groups.iter().find(|g| g.name == group) {
+        None => return Ok(None),
+        Some(g) => g,
+    };
+    let mut topics: Vec<String> = vec![];
+    for member in &group.members {
+        if let Some(payload) = &member.assignment {
+            for assgn in _parse_member_assignment(payload)? {
+                if !topics.contains(&assgn.topic) {
+                    topics.push(assgn.topic);
+                }
+            }
+        }
+    }
+    Ok(Some(topics))
+}
+
+pub fn get_topic_offsets(topic: &str) -> Result<Vec<dto::TopicOffsets>, ApiError> {
+    _get_offsets(topic)
+}
+
+pub fn get_partition_offsets(topic: &str, partition: i32) -> Result<dto::TopicOffsets, ApiError> {
+    _get_offsets_for_partition(topic, partition)
+}
+
+// returns the first offset of each of the partitions with a timestamp at or after the given timestamp
+// (or the high watermark if there is no such message).
+pub fn get_offsets_for_timestamp(topic: &str, offsets: &Vec<dto::TopicOffsets>, timestamp: i64) -> Result<HashMap<i32, i64>, ApiError> {
+    let timeout = Duration::from_secs(10);
+    let found = kafka_retry("fetching offsets for times", &mut || group_consumer("krowser"), &mut |consumer| {
+        let mut assignment = TopicPartitionList::new();
+        for offset in offsets {
+            assignment.add_partition_offset(topic, offset.partition, rdkafka::Offset::Offset(timestamp))?;
+        }
+        consumer.offsets_for_times(assignment, timeout)
+    })?;
+    let mut out = HashMap::with_capacity(offsets.len());
+    for elem in found.elements_for_topic(topic) {
+        let partition_offsets = match offsets.iter().find(|o| o.partition == elem.partition()) {
+            None => continue,
+            Some(o) => o,
+        };
+        let offset = match elem.offset() {
+            rdkafka::Offset::Offset(offset) => offset,
+            rdkafka::Offset::End => partition_offsets.high,
+            _ => return Err(ApiError::Internal(format!("bad offset type: {:?}", elem.offset()))),
+        };
+        out.insert(elem.partition(), offset);
+    }
+    Ok(out)
+}
+
+// commits the offsets right after the given (consumed) offsets for the group.
+pub fn commit_group_offsets(group: &str, topic: &str, offsets: &HashMap<i32, i64>) -> Result<(), ApiError> {
+    kafka_retry("committing offsets", &mut || group_consumer(group), &mut |consumer| {
+        let mut tpl = TopicPartitionList::new();
+        for (partition, offset) in offsets {
+            tpl.add_partition_offset(topic, *partition, rdkafka::Offset::Offset(offset + 1))?;
+        }
+        consumer.commit(&tpl, CommitMode::Sync)
+    })
+}
+
+pub fn get_group_topic_offsets(group: &str, topic: &str) -> Result<Vec<dto::ConsumerGroupOffsets>, ApiError> {
+    let offsets = _get_offsets(topic)?;
+    _get_committed_offsets(group, topic, &offsets)
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct MemberAssignment {
     pub topic: String,
@@ -575,7 +807,14 @@ fn _parse_member_assignment(payload: &[u8]) -> Result<Vec<MemberAssignment>, Str
     Ok(assigns)
 }
 
-#[get("/api/messages/<topic>/<partition>?<limit>&<offset>&<search>&<search_style>&<timeout_millis>&<trace>&<decoding>")]
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetTopicMessagesResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/messages/<topic>/<partition>?<limit>&<offset>&<search>&<search_style>&<timeout_millis>&<trace>&<decoding>&<fields>&<find>&<max_scan>")]
 pub async fn get_messages(
     topic: &str,
     partition: i32,
@@ -585,131 +824,192 @@ pub async fn get_messages(
     search_style: Option<dto::SearchStyle>,
     timeout_millis: Option<u64>,
     trace: bool,
-    decoding: Option<&str>) -> Result<Json<dto::GetTopicMessagesResult>, String> {
+    decoding: Option<&str>,
+    fields: Vec<&str>,
+    find: bool,
+    max_scan: Option<i64>,
+    user: User,
+    audit: &Audit) -> Result<Json<dto::GetTopicMessagesResult>, ApiError> {
+    check_topic_access(&user, topic)?;
 
     let limit = limit.unwrap_or(100);
     let offset = offset.unwrap_or(0);
     let timeout_millis = timeout_millis.unwrap_or(20000);
     let search_style = search_style.unwrap_or(dto::SearchStyle::None);
     let decoding = decoding.unwrap_or("");
-    eprintln!("{:?} {:?} {} {}", search, search_style, timeout_millis, decoding);
-    match timeout(Duration::from_millis(timeout_millis),
-        _get_messages(topic, partition, limit, offset, search, search_style, trace, decoding)).await {
-            Err(_) => Ok(Json(dto::GetTopicMessagesResult{has_timeout: true, messages: Vec::new()})),
-            Ok(res) => res,
+    let masker = Masker::new(topic, &user);
+    let projection = Projection::new(&fields)?;
+    // in find mode the limit is the number of matches to find, and the scan is bounded by max_scan and the timeout.
+    let scan = if find {
+        Scan::Find{max_scan: max_scan.unwrap_or(DEFAULT_MAX_SCAN), deadline: tokio::time::Instant::now() + Duration::from_millis(timeout_millis)}
+    } else {
+        Scan::Window
+    };
+    debug!(?search, ?search_style, timeout_millis, decoding, ?fields, find, "fetching messages");
+    let res = match scan {
+        // find mode stops at the deadline by itself, and returns the matches found until then.
+        Scan::Find{..} => _get_messages(topic, partition, limit, offset, search, search_style, trace, decoding, &masker, projection.as_ref(), scan).await?,
+        Scan::Window => match timeout(Duration::from_millis(timeout_millis),
+            _get_messages(topic, partition, limit, offset, search, search_style, trace, decoding, &masker, projection.as_ref(), scan)).await {
+                Err(_) => return Ok(Json(dto::GetTopicMessagesResult{has_timeout: true, messages: Vec::new(), scanned: 0, last_offset: None})),
+                Ok(res) => res?,
+        },
+    };
+    if let (Some(first), Some(last)) = (res.messages.first(), res.messages.last()) {
+        audit.set_offsets(first.offset, last.offset);
     }
+    audit.set_result_count(res.messages.len());
+    Ok(Json(res))
+}
+
+const DEFAULT_MAX_SCAN: i64 = 100000;
+
+#[derive(Clone, Copy)]
+enum Scan {
+    // scans exactly `limit` messages
+    Window,
+    // scans until `limit` messages match
+    Find{max_scan: i64, deadline: tokio::time::Instant},
 }
 
 async fn _get_messages(
     topic: &str,
     partition: i32,
-    mut limit: i64,
+    limit: i64,
     offset: i64,
     search: Option<&str>,
     search_style: dto::SearchStyle,
     trace: bool,
-    decoding: &str) -> Result<Json<dto::GetTopicMessagesResult>, String> {
-    let regex: Option<Regex> = match search_style {
-        dto::SearchStyle::Regex =>
-            if let Some(pattern) = &search
-                { Some(map_error(Regex::new(pattern))?) } else
-                { None },
-        _ => None,
-    };
+    decoding: &str,
+    masker: &Masker,
+    projection: Option<&Projection>,
+    scan: Scan) -> Result<dto::GetTopicMessagesResult, ApiError> {
+    let search = Search::new(search, search_style)?;
     let offsets = _get_offsets_for_partition(topic, partition)?;
     let max_offset = offsets.high;
+    let empty = dto::GetTopicMessagesResult{messages: Vec::new(), has_timeout: false, scanned: 0, last_offset: None};
     if max_offset == 0 || offset > max_offset {
-        return Ok(Json(dto::GetTopicMessagesResult{messages: Vec::new(), has_timeout: false}))
+        return Ok(empty)
     }
-    if offset + limit > max_offset {
-        limit = max_offset - offset
+    let (max_matches, mut max_scanned) = match scan {
+        Scan::Window => (limit, limit),
+        Scan::Find{max_scan, ..} => (limit, max_scan),
+    };
+    if offset + max_scanned > max_offset {
+        max_scanned = max_offset - offset
     }
-    if limit <= 0 {
-        return Ok(Json(dto::GetTopicMessagesResult{messages: Vec::new(), has_timeout: false}))
+    if max_scanned <= 0 || max_matches <= 0 {
+        return Ok(empty)
     }
 
-    println!("Connecting to kafka at: {}", (*config::SETTINGS).kafka.urls);
-    let consumer: LoggingConsumer = retry("connecting consumer", &mut || ClientConfig::new()
-        .set("bootstrap.servers", &(*config::SETTINGS).kafka.urls)
-        .set("group.id", "krowser")
-        .set("enable.auto.commit", "false")
-        .create_with_context(CustomContext))?;
+    let consumer = stream_consumer()?;
 
     let mut assignment = TopicPartitionList::new();
     map_error(assignment.add_partition_offset(topic, partition, rdkafka::Offset::Offset(offset)))?;
-    retry("assigning consumer", &mut || consumer.assign(&assignment))?;
+    retry("assigning consumer", &mut || consumer.assign(&assignment)).map_err(ApiError::KafkaUnavailable)?;
 
-    let key_decoders: Vec<&Box<dyn Decoder>>;
-    let value_decoders: Vec<&Box<dyn Decoder>>;
-    unsafe {
-        if decoding == "" || decoding == "Auto-Detect" {
-            key_decoders = DECODERS.get_decoders(topic.to_string(), true);
-            value_decoders = DECODERS.get_decoders(topic.to_string(), false);
-        } else {
-            let decoder = DECODERS.get_decoder(decoding)?;
-            key_decoders = vec![decoder];
-            value_decoders = vec![decoder];
-        }
-    }
+    let (key_decoders, value_decoders) = message_decoders(topic, decoding)?;
 
     let mut num_consumed = 0;
-    let mut messages = Vec::with_capacity(limit.try_into().unwrap());
+    let mut last_offset = None;
+    let mut has_timeout = false;
+    let mut messages = Vec::with_capacity(max_matches.min(max_scanned).try_into().unwrap());
 
     let mut message_stream = consumer.stream();
-    while let Some(message) = message_stream.next().await {
+    loop {
+        let message = match scan {
+            Scan::Window => message_stream.next().await,
+            Scan::Find{deadline, ..} => match tokio::time::timeout_at(deadline, message_stream.next()).await {
+                Err(_) => {
+                    has_timeout = true;
+                    break;
+                },
+                Ok(message) => message,
+            },
+        };
+        let message = match message {
+            None => break,
+            Some(message) => message,
+        };
         num_consumed += 1;
         match message {
-            Err(e) => eprintln!("Kafka error: {}", e),
+            Err(e) => error!("Kafka error: {}", e),
             Ok(m) => {
-                let owned = m.detach();
-                let owned_search = search.map(|s| s.to_string());
-                let owned_regex = regex.clone();
-                let owned_key_decoders = key_decoders.to_vec();
-                let owned_value_decoders = value_decoders.to_vec();
-                let msg = parse_message(owned, partition, owned_search, search_style, trace, owned_regex, owned_key_decoders, owned_value_decoders).await?;
-                if let Some(message) = msg {
+                last_offset = Some(m.offset());
+                let msg = parse_message(m.detach(), partition, search.as_ref(), trace, &key_decoders, &value_decoders, masker).await?;
+                if let Some(mut message) = msg {
+                    // projecting after the search, so the search can use fields which are not returned.
+                    if let Some(projection) = projection {
+                        message.key = projection.project_key(message.key);
+                        message.value = projection.project_value(message.value);
+                    }
                     messages.push(message);
                 }
             }
         };
-        if num_consumed >= limit {
+        if num_consumed >= max_scanned || messages.len() as i64 >= max_matches {
+            break;
+        }
+        // compacted topics have gaps in their offsets, so the count alone won't reach the end.
+        if last_offset.map_or(false, |o| o >= max_offset - 1) {
             break;
         }
     }
 
-    Ok(Json(dto::GetTopicMessagesResult{
+    Ok(dto::GetTopicMessagesResult{
         messages: messages,
-        has_timeout: false,
-    }))
+        has_timeout: has_timeout,
+        scanned: num_consumed,
+        last_offset: last_offset,
+    })
+}
+
+pub fn stream_consumer() -> Result<LoggingConsumer, ApiError> {
+    debug!("Connecting to kafka at: {}", (*config::SETTINGS).kafka.urls);
+    retry("connecting consumer", &mut || ClientConfig::new()
+        .set("bootstrap.servers", &(*config::SETTINGS).kafka.urls)
+        .set("group.id", "krowser")
+        .set("enable.auto.commit", "false")
+        .create_with_context(CustomContext)).map_err(ApiError::KafkaUnavailable)
+}
+
+// returns the key and value decoders to try for the topic, either the configured ones (auto-detect) or a specific decoder.
+pub fn message_decoders(topic: &str, decoding: &str) -> Result<(Vec<&'static Box<dyn Decoder>>, Vec<&'static Box<dyn Decoder>>), ApiError> {
+    unsafe {
+        if decoding == "" || decoding == "Auto-Detect" {
+            Ok((DECODERS.get_decoders(topic.to_string(), true), DECODERS.get_decoders(topic.to_string(), false)))
+        } else {
+            let decoder = DECODERS.get_decoder(decoding).map_err(ApiError::DecoderNotFound)?;
+            Ok((vec![decoder], vec![decoder]))
+        }
+    }
 }
 
-async fn parse_message(
+pub async fn parse_message(
     m: OwnedMessage,
     partition: i32,
-    search: Option<String>,
-    search_style: dto::SearchStyle,
+    search: Option<&Search>,
     trace: bool,
-    regex: Option<Regex>,
-    key_decoders: Vec<&Box<dyn Decoder>>,
-    value_decoders: Vec<&Box<dyn Decoder>>
-) -> Result<Option<dto::TopicMessage>, String> {
+    key_decoders: &Vec<&Box<dyn Decoder>>,
+    value_decoders: &Vec<&Box<dyn Decoder>>,
+    masker: &Masker,
+) -> Result<Option<dto::TopicMessage>, ApiError> {
     let topic = m.topic();
     let timestamp = match m.timestamp() {
         Timestamp::NotAvailable => 0,
         Timestamp::CreateTime(v) => v,
         Timestamp::LogAppendTime(v) => v,
     };
-    let decoded_value = decode(&m, DecodingAttribute::Value, &value_decoders).await?;
-    let decoded_key = decode(&m, DecodingAttribute::Key, &key_decoders).await?;
-    let json_key = &decoded_key.contents.json.unwrap();
-    let json_value = &decoded_value.contents.json.unwrap();
+    let decoded_value = decode(&m, DecodingAttribute::Value, value_decoders).await?;
+    let decoded_key = decode(&m, DecodingAttribute::Key, key_decoders).await?;
+    let json_key = &masker.mask(decoded_key.contents.json.unwrap());
+    let json_value = &masker.mask(decoded_value.contents.json.unwrap());
     if trace {
-        eprintln!("key: '{:?}', value: {:?}, topic: {}, offset: {}, timestamp: {:?}",
+        info!("key: '{:?}', value: {:?}, topic: {}, offset: {}, timestamp: {:?}",
             json_key, json_value, m.topic(), m.offset(), timestamp);
     }
-    if let Some(pattern) = search {
-        let text = format!("{},{}", json_key, json_value);
-        if !includes(text, pattern.to_string(), &search_style, &regex) {
+    if let Some(search) = search {
+        if !search.matches(json_key, json_value) {
             return Ok(None);
         }
     }
@@ -731,12 +1031,20 @@ pub struct DecodedMessage {
     pub decoding: String,
 }
 
-async fn decode(message: &OwnedMessage, attr: DecodingAttribute, decoders: &Vec<&Box<dyn Decoder>>) -> Result<DecodedMessage, String>{
+async fn decode(message: &OwnedMessage, attr: DecodingAttribute, decoders: &Vec<&Box<dyn Decoder>>) -> Result<DecodedMessage, ApiError> {
     for decoder in decoders {
-        let result = decoder.decode(message, &attr).await?;
+        let result = match decoder.decode(message, &attr).await {
+            Ok(result) => result,
+            Err(e) => {
+                metrics::inc_decoder_result(decoder.id(), "error");
+                return Err(e.into());
+            }
+        };
         if let Some(_) = result.json {
+            metrics::inc_decoder_result(decoder.id(), "success");
             return Ok(DecodedMessage{contents: result, decoding: decoder.display_name().to_string()});
         }
+        metrics::inc_decoder_result(decoder.id(), "no-match");
     }
     let payload = message.payload();
     match payload {
@@ -745,43 +1053,99 @@ async fn decode(message: &OwnedMessage, attr: DecodingAttribute, decoders: &Vec<
     }
 }
 
-fn includes(text: String, pattern: String, style: &dto::SearchStyle, regex: &Option<Regex>) -> bool {
-    match style {
-        dto::SearchStyle::None => text.to_ascii_lowercase().contains(&pattern.to_ascii_lowercase()),
-        dto::SearchStyle::CaseSensitive => text.contains(&pattern),
-        dto::SearchStyle::Regex => (*regex).as_ref().unwrap().is_match(&text),
+// hits and misses of the caches generated by the `cached` macro.
+pub fn cache_stats() -> Vec<(&'static str, u64, u64)> {
+    let mut stats = vec![];
+    {
+        let cache = CACHED_GET_METADATA.lock().unwrap();
+        stats.push(("metadata", cache.cache_hits().unwrap_or(0), cache.cache_misses().unwrap_or(0)));
     }
+    {
+        let cache = CACHED_GET_TOPIC.lock().unwrap();
+        stats.push(("topic", cache.cache_hits().unwrap_or(0), cache.cache_misses().unwrap_or(0)));
+    }
+    {
+        let cache = CACHED_FETCH_GROUP_LIST.lock().unwrap();
+        stats.push(("groups", cache.cache_hits().unwrap_or(0), cache.cache_misses().unwrap_or(0)));
+    }
+    stats
 }
 
 pub fn update_cache_thread() {
     std::thread::spawn(|| {
         loop {
-            eprintln!("Refreshing topic cache");
+            debug!("Refreshing topic cache");
             let start = Instant::now();
             match cached_get_metadata_prime_cache() {
                 Ok(_) => {},
-                Err(err) => { eprintln!("failed refreshing metadata cache: {}", err); }
+                Err(err) => { error!("failed refreshing metadata cache: {}", err); }
             }
             match cached_fetch_group_list_prime_cache() {
                 Ok(_) => {},
-                Err(err) => { eprintln!("failed refreshing groups cache: {}", err); }
+                Err(err) => { error!("failed refreshing groups cache: {}", err); }
             }
-            match get_topics() {
+            match cached_get_metadata() {
                 Ok(topics) => {
-                    topics.into_inner().topics.iter().for_each(|topic| {
+                    topics.iter().for_each(|topic| {
                         // this method is generated by the `cached` macro
                         match cached_get_topic_prime_cache(topic.name.to_string()) {
                             Ok(_) => {},
-                            Err(err) => { eprintln!("failed refreshing topic cache for {}: {}", topic.name.to_string(), err); }
+                            Err(err) => { error!("failed refreshing topic cache for {}: {}", topic.name.to_string(), err); }
                         }
                     });
                     sleep(Duration::from_millis(100));
                 },
                 Err(err) => {
-                    eprintln!("error getting topics: {}", err);
+                    error!("error getting topics: {}", err);
                 }
             }
-            eprintln!("Refreshed topic cache in {:?}", start.elapsed());
+            metrics::observe_cache_refresh(start);
+            info!("Refreshed topic cache in {:?}", start.elapsed());
         }
     });
 }
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_topics,
+        get_topic,
+        get_topic_configs,
+        get_broker_configs,
+        get_cluster,
+        get_groups,
+        get_group_members,
+        get_decoders,
+        get_topic_consumer_groups,
+        get_offsets,
+        get_offset_for_timestamp,
+        get_messages,
+    ),
+    components(schemas(
+        dto::PartitionMetadata,
+        dto::BrokerMetadata,
+        dto::TopicMetadata,
+        dto::GetTopicsResult,
+        dto::GetClusterResult,
+        dto::TopicOffsets,
+        dto::ConsumerGroupOffsets,
+        dto::TopicConsumerGroup,
+        dto::GetTopicOffsetsResult,
+        dto::GetTopicResult,
+        dto::GetTopicConsumerGroupsResult,
+        dto::GroupMemberMetadata,
+        dto::GroupMetadata,
+        dto::GetGroupsResult,
+        dto::GetGroupMembersResult,
+        dto::ConfigEntry,
+        dto::GetTopicConfigsResult,
+        dto::GetBrokerConfigsResult,
+        dto::TopicMessage,
+        dto::GetTopicMessagesResult,
+        dto::GetOffsetForTimestampResult,
+        dto::SearchStyle,
+        dto::DecoderMetadata,
+        dto::GetDecodersResult,
+    )),
+)]
+pub struct ApiDoc;
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use rdkafka::Message;
+use rdkafka::message::{Headers, OwnedMessage};
+use rocket::serde::json::Json;
+use tokio::sync::Mutex;
+use tokio::time::{timeout_at, Instant};
+use tracing::{debug, info, warn};
+use utoipa::OpenApi;
+
+use crate::config;
+use crate::auth::guard::User;
+use crate::audit::log::Audit;
+use crate::common::errors::{ApiError, ErrorResult};
+use crate::kafka::api;
+use crate::kafka::dto;
+use crate::kafka::masking::Masker;
+use crate::kafka::ranges::{PartitionRange, RangeReader};
+use crate::kafka::search::Search;
+
+// views which were not requested for this long are dropped.
+const VIEW_TTL: Duration = Duration::from_secs(30 * 60);
+
+// The latest (raw) message of every key in a partition of a compacted topic, up to an offset. It is shared by all
+// users (the messages are decoded and masked per request) and refreshed from where the previous scan stopped.
+struct View {
+    latest: HashMap<Vec<u8>, OwnedMessage>,
+    // the next offset to read
+    next_offset: i64,
+    // the size of the keys, values and headers in the view
+    size: usize,
+    // the view reached the configured limits, and stopped reading at next_offset
+    truncated: bool,
+    last_used: Instant,
+}
+
+impl View {
+    fn new() -> View {
+        View{latest: HashMap::new(), next_offset: 0, size: 0, truncated: false, last_used: Instant::now()}
+    }
+
+    fn clear(&mut self) {
+        self.latest.clear();
+        self.size = 0;
+        self.truncated = false;
+    }
+
+    // applies the message to the view, returns false (without applying it) if it would exceed the limits.
+    fn apply(&mut self, message: OwnedMessage) -> bool {
+        let key = match message.key() {
+            None => return true,
+            Some(key) => key.to_vec(),
+        };
+        let old_size = self.latest.get(&key).map_or(0, message_size);
+        if message.payload().is_none() {
+            self.latest.remove(&key);
+            self.size -= old_size;
+            return true;
+        }
+        let limits = &(*config::SETTINGS).compacted_views;
+        let size = self.size - old_size + message_size(&message);
+        if (old_size == 0 && self.latest.len() >= limits.max_keys) || size > limits.max_size_mb * 1024 * 1024 {
+            return false;
+        }
+        self.latest.insert(key, message);
+        self.size = size;
+        true
+    }
+}
+
+fn message_size(message: &OwnedMessage) -> usize {
+    let headers = message.headers().map_or(0, |h| (0..h.count()).filter_map(|i| h.get(i)).map(|(k, v)| k.len() + v.len()).sum());
+    message.key().map_or(0, |k| k.len()) + message.payload().map_or(0, |v| v.len()) + headers
+}
+
+static VIEWS: Lazy<DashMap<(String, i32), Arc<Mutex<View>>>> = Lazy::new(DashMap::new);
+
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::CompactedViewResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/compacted/<topic>/<partition>?<offset>&<limit>&<search>&<search_style>&<decoding>&<timeout_millis>")]
+pub async fn get_compacted_view(
+    topic: &str,
+    partition: i32,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    search: Option<&str>,
+    search_style: Option<dto::SearchStyle>,
+    decoding: Option<&str>,
+    timeout_millis: Option<u64>,
+    user: User,
+    audit: &Audit) -> Result<Json<dto::CompactedViewResult>, ApiError> {
+    api::check_topic_access(&user, topic)?;
+    check_compacted(topic).await?;
+
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(100);
+    let search = Search::new(search, search_style.unwrap_or(dto::SearchStyle::None))?;
+    let (key_decoders, value_decoders) = api::message_decoders(topic, decoding.unwrap_or(""))?;
+    let masker = Masker::new(topic, &user);
+    let deadline = Instant::now() + Duration::from_millis(timeout_millis.unwrap_or(20000));
+
+    let view = get_view(topic, partition);
+    let mut view = view.lock().await;
+    view.last_used = Instant::now();
+    let has_timeout = refresh(topic, partition, &mut view, deadline).await?;
+
+    // sorting by the raw keys, so the pages are stable between requests.
+    let mut keys: Vec<&Vec<u8>> = view.latest.keys().collect();
+    keys.sort();
+    let mut entries = vec![];
+    let mut total = 0;
+    for key in keys {
+        // without a search, only the requested page has to be decoded.
+        if search.is_none() && (total < offset || total >= offset + limit) {
+            total += 1;
+            continue;
+        }
+        let message = view.latest[key].clone();
+        if let Some(message) = api::parse_message(message, partition, search.as_ref(), false, &key_decoders, &value_decoders, &masker).await? {
+            if total >= offset && total < offset + limit {
+                entries.push(message);
+            }
+            total += 1;
+        }
+    }
+    audit.set_result_count(entries.len());
+    Ok(Json(dto::CompactedViewResult{
+        entries: entries,
+        keys: view.latest.len(),
+        total: total,
+        offset: offset,
+        last_offset: if view.next_offset > 0 { Some(view.next_offset - 1) } else { None },
+        truncated: view.truncated,
+        has_timeout: has_timeout,
+    }))
+}
+
+// the view is only built for compacted topics, on other topics it would hold every message.
+async fn check_compacted(topic: &str) -> Result<(), ApiError> {
+    let entries = api::fetch_topic_configs(topic).await?;
+    let policy = entries.iter().find(|e| e.name == "cleanup.policy").and_then(|e| e.value.clone()).unwrap_or_default();
+    if !policy.split(',').any(|p| p.trim() == "compact") {
+        return Err(ApiError::BadRequest(format!("topic {} is not compacted (cleanup.policy is \"{}\")", topic, policy)));
+    }
+    Ok(())
+}
+
+fn get_view(topic: &str, partition: i32) -> Arc<Mutex<View>> {
+    let id = (topic.to_string(), partition);
+    VIEWS.retain(|_, view| view.try_lock().map_or(true, |view| view.last_used.elapsed() < VIEW_TTL));
+    if !VIEWS.contains_key(&id) {
+        // dropping the least recently used views (which are not being refreshed right now) to make room.
+        let max_views = (*config::SETTINGS).compacted_views.max_views.max(1);
+        let mut unused: Vec<((String, i32), Instant)> = VIEWS.iter()
+            .filter_map(|entry| entry.value().try_lock().ok().map(|view| (entry.key().clone(), view.last_used)))
+            .collect();
+        unused.sort_by_key(|(_, last_used)| *last_used);
+        let excess = (VIEWS.len() + 1).saturating_sub(max_views);
+        for (id, _) in unused.into_iter().take(excess) {
+            VIEWS.remove(&id);
+        }
+    }
+    VIEWS.entry(id).or_insert_with(|| Arc::new(Mutex::new(View::new()))).clone()
+}
+
+// reads the partition from where the previous refresh stopped up to the current high watermark, and returns whether
+// it stopped before reaching it (the next refresh continues from there).
+async fn refresh(topic: &str, partition: i32, view: &mut View, deadline: Instant) -> Result<bool, ApiError> {
+    let offsets = api::get_partition_offsets(topic, partition)?;
+    if offsets.low > view.next_offset && view.next_offset > 0 {
+        // the topic is also cleaned up by retention (or was recreated), so the view is rebuilt.
+        info!("offset {} of topic {} partition {} was removed, rebuilding its compacted view", view.next_offset, topic, partition);
+        view.clear();
+    }
+    if offsets.high < view.next_offset {
+        info!("topic {} partition {} was truncated, rebuilding its compacted view", topic, partition);
+        view.clear();
+        view.next_offset = 0;
+    }
+    if view.truncated {
+        return Ok(false);
+    }
+    let from = view.next_offset.max(offsets.low);
+    if from >= offsets.high {
+        view.next_offset = offsets.high;
+        return Ok(false);
+    }
+    debug!(topic, partition, from, to = offsets.high - 1, "refreshing compacted view");
+    let mut reader = RangeReader::new(topic, vec![PartitionRange{partition: partition, from: from, to: offsets.high - 1}])?;
+    view.next_offset = from;
+    loop {
+        let message = match timeout_at(deadline, reader.next()).await {
+            Err(_) => return Ok(true),
+            Ok(None) if reader.is_exhausted() => break,
+            // no messages arrived for a while, the offsets from next_offset on weren't read.
+            Ok(None) => return Ok(true),
+            Ok(Some(message)) => message,
+        };
+        let offset = message.offset();
+        if !view.apply(message) {
+            warn!("the compacted view of topic {} partition {} reached its limits at offset {}", topic, partition, offset);
+            view.truncated = true;
+            return Ok(false);
+        }
+        view.next_offset = offset + 1;
+    }
+    // the end offset might not be a message (i.e a transaction marker).
+    view.next_offset = offsets.high;
+    Ok(false)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_compacted_view,
+    ),
+    components(schemas(
+        dto::CompactedViewResult,
+        dto::TopicMessage,
+    )),
+)]
+pub struct ApiDoc;
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use rdkafka::Message;
+use rdkafka::message::{Headers, OwnedHeaders, OwnedMessage};
+use rdkafka::producer::FutureRecord;
+use rocket::serde::json::Json;
+use serverapi::Decoder;
+use tracing::{error, info};
+use utoipa::OpenApi;
+
+use crate::auth::guard::User;
+use crate::auth::roles::Action;
+use crate::common::capabilities;
+use crate::common::errors::{ApiError, ErrorResult};
+use crate::common::jobs::{Job, Jobs, JobStatus};
+use crate::kafka::api;
+use crate::kafka::dto;
+use crate::kafka::masking::Masker;
+use crate::kafka::produce::BatchProducer;
+use crate::kafka::ranges::{partition_ranges, RangeReader};
+use crate::kafka::search::Search;
+
+static COPY_JOBS: Lazy<Jobs<dto::CopyProgress>> = Lazy::new(Jobs::new);
+
+#[utoipa::path(
+    tag = "kafka",
+    request_body = dto::CopyRequest,
+    responses(
+        (status = 200, body = dto::CopyJobResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[post("/api/copy", data = "<request>")]
+pub async fn start_copy(request: Json<dto::CopyRequest>, user: User) -> Result<Json<dto::CopyJobResult>, ApiError> {
+    let request = request.into_inner();
+    api::check_topic_access(&user, &request.source_topic)?;
+    if !request.dry_run {
+        if !user.can(Action::Produce) {
+            return Err(ApiError::Forbidden(format!("user {} is not allowed to perform {} actions", user.name, Action::Produce.as_str())));
+        }
+        api::check_topic_access(&user, &request.destination_topic)?;
+        api::get_topic_partitions(&request.destination_topic)?;
+    }
+    if let Some(group) = &request.commit_group {
+        if !capabilities::capabilities().group_admin {
+            return Err(ApiError::Forbidden("committing consumer group offsets is disabled".to_string()));
+        }
+        if !user.can_read_group(group) {
+            return Err(ApiError::Forbidden(format!("user {} is not allowed to access consumer group {}", user.name, group)));
+        }
+        // all of the scanned offsets are committed, so filtered out messages would be skipped by the group as well.
+        if request.search.is_some() || !request.header_filters.is_empty() {
+            return Err(ApiError::BadRequest("moving messages (committing the group offsets) can't be combined with filters".to_string()));
+        }
+    }
+
+    let search = Search::new(request.search.as_deref(), request.search_style.unwrap_or(dto::SearchStyle::None))?;
+    let (key_decoders, value_decoders) = api::message_decoders(&request.source_topic, request.decoding.as_deref().unwrap_or(""))?;
+    let masker = Masker::new(&request.source_topic, &user);
+    // the raw messages are copied, the destination topic might not mask them.
+    if !request.dry_run && !masker.is_empty() {
+        return Err(ApiError::Forbidden(format!("topic {} has masked fields, its messages can't be copied (only counted with a dry run)", request.source_topic)));
+    }
+    let ranges = partition_ranges(&request.source_topic, request.partitions.clone(), request.from_offset, request.to_offset, request.from_time, request.to_time)?;
+    let reader = RangeReader::new(&request.source_topic, ranges)?;
+
+    let job = COPY_JOBS.start(&user, dto::CopyProgress{total: reader.total(), dry_run: request.dry_run, ..Default::default()})?;
+    info!("user {} started copying from topic {} to topic {} (job {}, dry run: {})", user.name, request.source_topic, request.destination_topic, job.id, request.dry_run);
+    let copy = Copy{
+        job: Arc::clone(&job),
+        request: request,
+        search: search,
+        key_decoders: key_decoders,
+        value_decoders: value_decoders,
+        masker: masker,
+    };
+    tokio::spawn(async move {
+        let result = copy.run(reader).await;
+        if let Err(e) = &result {
+            error!("copy job {} failed: {}", copy.job.id, e);
+        }
+        copy.job.finish(result);
+    });
+
+    Ok(Json(job_result(&job)))
+}
+
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::CopyJobResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/copy/<id>")]
+pub fn get_copy(id: &str, user: User) -> Result<Json<dto::CopyJobResult>, ApiError> {
+    let job = COPY_JOBS.get(id, &user)?;
+    Ok(Json(job_result(&job)))
+}
+
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::CopyJobResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[delete("/api/copy/<id>")]
+pub fn cancel_copy(id: &str, user: User) -> Result<Json<dto::CopyJobResult>, ApiError> {
+    let job = COPY_JOBS.get(id, &user)?;
+    job.cancel();
+    Ok(Json(job_result(&job)))
+}
+
+fn job_result(job: &Job<dto::CopyProgress>) -> dto::CopyJobResult {
+    let (status, progress, failure) = job.snapshot();
+    dto::CopyJobResult{
+        id: job.id.to_string(),
+        status: status,
+        failure: failure,
+        progress: progress,
+    }
+}
+
+struct Copy {
+    job: Arc<Job<dto::CopyProgress>>,
+    request: dto::CopyRequest,
+    search: Option<Search>,
+    key_decoders: Vec<&'static Box<dyn Decoder>>,
+    value_decoders: Vec<&'static Box<dyn Decoder>>,
+    masker: Masker,
+}
+
+impl Copy {
+    // copies the raw bytes of the matching messages, the decoders are only used for filtering.
+    async fn run(&self, mut reader: RangeReader) -> Result<(), ApiError> {
+        let mut producer = if self.request.dry_run { None } else { Some(BatchProducer::new()?) };
+        let mut last_offsets: HashMap<i32, i64> = HashMap::new();
+        while !self.job.is_cancelled() {
+            let message = match reader.next().await {
+                None => break,
+                Some(message) => message,
+            };
+            self.job.update(|p| p.scanned += 1);
+            last_offsets.insert(message.partition(), message.offset());
+            if !self.matches(&message).await? {
+                continue;
+            }
+            self.job.update(|p| p.matched += 1);
+            if let Some(producer) = &mut producer {
+                let mut record: FutureRecord<[u8], [u8]> = FutureRecord::to(&self.request.destination_topic).headers(self.rewrite_headers(&message));
+                if let Some(key) = message.key() {
+                    record = record.key(key);
+                }
+                if let Some(payload) = message.payload() {
+                    record = record.payload(payload);
+                }
+                if self.request.keep_partitions {
+                    record = record.partition(message.partition());
+                }
+                if let (true, Some(timestamp)) = (self.request.keep_timestamps, message.timestamp().to_millis()) {
+                    record = record.timestamp(timestamp);
+                }
+                producer.send(record).await?;
+                self.job.update(|p| p.produced += 1);
+            }
+        }
+        if let Some(producer) = &mut producer {
+            producer.flush().await?;
+        }
+        // the rest of the range wasn't copied, so the group offsets aren't committed either.
+        if !reader.is_exhausted() && !self.job.is_cancelled() {
+            return Err(ApiError::Timeout(format!("the copy stopped after being idle (no messages arrived from topic {})", self.request.source_topic)));
+        }
+        if let Some(group) = &self.request.commit_group {
+            if !self.request.dry_run && !self.job.is_cancelled() {
+                api::commit_group_offsets(group, &self.request.source_topic, &last_offsets)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn matches(&self, message: &OwnedMessage) -> Result<bool, ApiError> {
+        for (key, value) in &self.request.header_filters {
+            if header_value(message, key).as_ref() != Some(value) {
+                return Ok(false);
+            }
+        }
+        if self.search.is_none() {
+            return Ok(true);
+        }
+        let parsed = api::parse_message(message.clone(), message.partition(), self.search.as_ref(), false, &self.key_decoders, &self.value_decoders, &self.masker).await?;
+        Ok(parsed.is_some())
+    }
+
+    fn rewrite_headers(&self, message: &OwnedMessage) -> OwnedHeaders {
+        let mut headers = OwnedHeaders::new();
+        if let Some(message_headers) = message.headers() {
+            for index in 0..message_headers.count() {
+                if let Some((key, value)) = message_headers.get(index) {
+                    if self.request.remove_headers.iter().any(|h| h == key) || self.request.set_headers.contains_key(key) {
+                        continue;
+                    }
+                    headers = headers.add(key, value);
+                }
+            }
+        }
+        for (key, value) in &self.request.set_headers {
+            headers = headers.add(key, value);
+        }
+        headers
+    }
+}
+
+fn header_value(message: &OwnedMessage, key: &str) -> Option<String> {
+    let headers = message.headers()?;
+    (0..headers.count())
+        .filter_map(|index| headers.get(index))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| String::from_utf8_lossy(v).to_string())
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        start_copy,
+        get_copy,
+        cancel_copy,
+    ),
+    components(schemas(
+        dto::CopyRequest,
+        dto::CopyProgress,
+        dto::CopyJobResult,
+        JobStatus,
+    )),
+)]
+pub struct ApiDoc;
@@ -8,6 +8,7 @@ use serde_json::json;
 use serverapi::{Decoder, DecoderBuilder, DecodingAttribute, DecodedContents, Config};
 use schema_registry_converter::async_impl::schema_registry::SrSettings;
 use schema_registry_converter::async_impl::avro::AvroDecoder;
+use tracing::{debug, warn};
 
 #[derive(Debug, Default)]
 pub struct AvroConfluentDecoderBuilder {}
@@ -56,7 +57,7 @@ async fn decode_payload(avro_decoder: &AvroDecoder<'static>, payload: Option<&[u
             let task = avro_decoder.decode(payload).await;
             match task {
                 Err(err) => {
-                    eprintln!("error decoding avro: {}", err);
+                    debug!("error decoding avro: {}", err);
                     return Ok(DecodedContents{json: None});
                 },
                 Ok(val) => {
@@ -69,7 +70,7 @@ async fn decode_payload(avro_decoder: &AvroDecoder<'static>, payload: Option<&[u
                     let json;
                     match JsonValue::try_from(decoded_val) {
                         Err(err) => {
-                            eprintln!("error parsing json: {}", err);
+                            warn!("error parsing json: {}", err);
                             json = format!("error parsing json: {}", err);
                         },
                         Ok(mut json_val) => {
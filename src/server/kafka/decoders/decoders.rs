@@ -14,6 +14,7 @@ use libloading::{Library,Symbol};
 use std::fs;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use tracing::{debug, info};
 
 pub static mut DECODERS: Lazy<Decoders> = Lazy::new(|| {
     let decoders: Decoders = futures::executor::block_on(async {
@@ -48,14 +49,14 @@ impl Decoders {
         let decoders_dir = "./decoders";
         if !fs::metadata(decoders_dir).is_ok() {
             let dir = env::current_dir().unwrap().into_os_string().into_string().unwrap();
-            eprintln!("No custom decoders found, current dir {}", dir);
+            info!("No custom decoders found, current dir {}", dir);
             return Ok(());
         }
         let paths = map_error(fs::read_dir(decoders_dir))?;
 
         for path in paths {
             let file_path = path.unwrap().path().display().to_string();
-            eprintln!("Loading decoder from: {}", file_path);
+            info!("Loading decoder from: {}", file_path);
             map_error(self.load_plugin(file_path).await)?;
         }
         Ok(())
@@ -65,7 +66,7 @@ impl Decoders {
         let conf = DynamicConfig{};
         let conf_boxed: Box<dyn serverapi::Config + Send> = Box::new(conf);
         let decoder = decoder_builder.build(conf_boxed).await;
-        eprintln!("Installed decoder {}", decoder.id());
+        info!("Installed decoder {}", decoder.id());
         self.decoders.insert(decoder.id().to_string(), decoder);
     }
 
@@ -141,10 +142,10 @@ impl Decoders {
     /// Unload all plugins and loaded plugin libraries, making sure to fire
     /// their `on_plugin_unload()` methods so they can do any necessary cleanup.
     pub fn unload(&mut self) {
-        eprintln!("Unloading plugins");
+        info!("Unloading plugins");
 
         for (plugin_name, plugin) in self.decoders.drain() {
-            eprintln!("Firing on_unload for {:?}", plugin_name);
+            debug!("Firing on_unload for {:?}", plugin_name);
             plugin.on_unload();
         }
 
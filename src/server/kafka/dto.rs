@@ -1,6 +1,10 @@
 use rocket::serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
 
-#[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
+use crate::common::jobs::JobStatus;
+
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct PartitionMetadata {
     pub error_description: Option<String>,
     pub partition_id: i32,
@@ -9,66 +13,66 @@ pub struct PartitionMetadata {
     pub isr: Vec<i32>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct BrokerMetadata {
     pub id: i32,
     pub host: String,
     pub port: i32,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, ToSchema)]
 pub struct TopicMetadata {
     pub name: String,
     pub partitions: Vec<PartitionMetadata>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetTopicsResult {
     pub topics: Vec<TopicMetadata>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetClusterResult {
     pub brokers: Vec<BrokerMetadata>,
 }
 
-#[derive(Serialize, Copy, Clone)]
+#[derive(Serialize, Copy, Clone, ToSchema)]
 pub struct TopicOffsets {
     pub partition: i32,
     pub high: i64,
     pub low: i64,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, ToSchema)]
 pub struct ConsumerGroupOffsets {
     pub metadata: Option<String>,
     pub offset: i64,
     pub partition_offsets: TopicOffsets,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, ToSchema)]
 pub struct TopicConsumerGroup {
     pub group_id: String,
     pub offsets: Vec<ConsumerGroupOffsets>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetTopicOffsetsResult {
     pub offsets: Vec<TopicOffsets>
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, ToSchema)]
 pub struct GetTopicResult {
     pub offsets: Vec<TopicOffsets>,
     pub consumer_groups: Vec<TopicConsumerGroup>
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetTopicConsumerGroupsResult {
     pub consumer_groups: Vec<TopicConsumerGroup>
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GroupMemberMetadata {
     pub member_id: String,
     pub client_id: String,
@@ -77,7 +81,7 @@ pub struct GroupMemberMetadata {
     pub assignment: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GroupMetadata {
     pub name: String,
     pub protocol: String,
@@ -86,17 +90,17 @@ pub struct GroupMetadata {
     pub members: Vec<GroupMemberMetadata>
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetGroupsResult {
     pub groups: Vec<GroupMetadata>
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetGroupMembersResult {
     pub members: Vec<GroupMemberMetadata>
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ConfigEntry {
     pub name: String,
     pub value: Option<String>,
@@ -106,17 +110,17 @@ pub struct ConfigEntry {
     pub is_sensitive: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetTopicConfigsResult {
     pub entries: Vec<ConfigEntry>
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetBrokerConfigsResult {
     pub entries: Vec<ConfigEntry>
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, ToSchema)]
 pub struct TopicMessage {
     pub topic: String,
     pub partition: i32,
@@ -128,32 +132,237 @@ pub struct TopicMessage {
     pub value_decoding: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetTopicMessagesResult {
    pub messages: Vec<TopicMessage>,
    pub has_timeout: bool,
+   // the number of messages that were read (matching or not) and the offset of the last one, to resume a search from
+   pub scanned: i64,
+   pub last_offset: Option<i64>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetOffsetForTimestampResult {
     pub offset: i64,
 }
 
-#[derive(Serialize, FromFormField, Debug, Copy, Clone)]
+#[derive(Serialize, Deserialize, FromFormField, Debug, Copy, Clone, ToSchema)]
+#[serde(rename_all = "kebab-case")]
 pub enum SearchStyle {
+    // the ui's plain search style is empty.
+    #[serde(alias = "")]
     None,
     #[field(value = "case-sensitive")]
     CaseSensitive,
     Regex,
+    Query,
+}
+
+#[derive(Serialize, FromFormField, Debug, Copy, Clone, PartialEq, ToSchema)]
+pub enum ExportFormat {
+    Ndjson,
+    Csv,
+    Avro,
+}
+
+#[derive(Serialize, FromFormField, Debug, Copy, Clone, PartialEq, ToSchema)]
+pub enum ImportPartitioning {
+    Preserve,
+    Remap,
+    Partitioner,
+}
+
+#[derive(Serialize, FromFormField, Debug, Copy, Clone, PartialEq, ToSchema)]
+pub enum ImportTimestamps {
+    Keep,
+    Now,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ImportResult {
+    pub produced: u64,
+    pub skipped: u64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CopyRequest {
+    pub source_topic: String,
+    pub destination_topic: String,
+    pub partitions: Option<Vec<i32>>,
+    pub from_offset: Option<i64>,
+    pub to_offset: Option<i64>,
+    pub from_time: Option<i64>,
+    pub to_time: Option<i64>,
+    pub search: Option<String>,
+    pub search_style: Option<SearchStyle>,
+    pub decoding: Option<String>,
+    #[serde(default)]
+    pub header_filters: HashMap<String, String>,
+    #[serde(default)]
+    pub set_headers: HashMap<String, String>,
+    #[serde(default)]
+    pub remove_headers: Vec<String>,
+    #[serde(default)]
+    pub keep_partitions: bool,
+    #[serde(default)]
+    pub keep_timestamps: bool,
+    pub commit_group: Option<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Clone, Default, ToSchema)]
+pub struct CopyProgress {
+    pub dry_run: bool,
+    pub total: i64,
+    pub scanned: u64,
+    pub matched: u64,
+    pub produced: u64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CopyJobResult {
+    pub id: String,
+    pub status: JobStatus,
+    pub failure: Option<String>,
+    pub progress: CopyProgress,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SearchJobRequest {
+    #[serde(default)]
+    pub topics: Vec<String>,
+    pub topic_regex: Option<String>,
+    pub from_time: Option<i64>,
+    pub to_time: Option<i64>,
+    pub search: String,
+    pub search_style: Option<SearchStyle>,
+    pub decoding: Option<String>,
+    pub max_results: Option<usize>,
+}
+
+#[derive(Serialize, Clone, Default, ToSchema)]
+pub struct SearchProgress {
+    pub topics: Vec<String>,
+    pub partitions: usize,
+    pub completed_partitions: usize,
+    pub total: i64,
+    pub scanned: u64,
+    pub matched: u64,
+    // the search stopped after reaching the maximum number of results
+    pub truncated: bool,
+    // the partitions (as topic/partition) whose reading stopped after being idle, so their matches might be missing
+    pub idle_partitions: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchJobResult {
+    pub id: String,
+    pub status: JobStatus,
+    pub failure: Option<String>,
+    pub progress: SearchProgress,
+    // a page of the matches found so far, starting at offset (in the order they were found)
+    pub offset: usize,
+    pub messages: Vec<TopicMessage>,
+}
+
+// The partitioner that was used to produce the messages: murmur2 is the default of the java client, crc32 is the
+// "consistent" partitioner of librdkafka and consistent-random is the default of librdkafka (crc32, but random for empty keys).
+#[derive(Serialize, FromFormField, Debug, Copy, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Partitioner {
+    Murmur2,
+    Crc32,
+    #[field(value = "consistent-random")]
+    ConsistentRandom,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct KeyLookupResult {
+    // the partitions that were searched
+    pub partitions: Vec<i32>,
+    // the newest messages with the key, newest first
+    pub messages: Vec<TopicMessage>,
+    pub scanned: i64,
+    pub has_timeout: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CompactedViewResult {
+    // a page (starting at offset) of the latest messages of the keys matching the search, sorted by their raw keys
+    pub entries: Vec<TopicMessage>,
+    // the number of live keys (not deleted by a tombstone) in the partition
+    pub keys: usize,
+    // the number of keys matching the search
+    pub total: usize,
+    pub offset: usize,
+    // the last offset the view was built up to
+    pub last_offset: Option<i64>,
+    // the view reached the configured limits (of keys or size) and doesn't include the offsets after last_offset
+    pub truncated: bool,
+    // the view wasn't refreshed up to the end of the partition (the next request continues from last_offset)
+    pub has_timeout: bool,
+}
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, ToSchema)]
+pub enum TimestampType {
+    NotAvailable,
+    CreateTime,
+    LogAppendTime,
+}
+
+#[derive(Serialize, FromFormField, Debug, Copy, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum RawFormat {
+    Base64,
+    Hex,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MessageHeader {
+    pub key: String,
+    pub value: String,
+    // withheld (like the raw key and value) when the topic is masked for the user
+    pub raw: Option<String>,
+}
+
+// the result of a single decoder for the key or the value of a message.
+#[derive(Serialize, ToSchema)]
+pub struct MessageDecoding {
+    pub attribute: String,
+    pub decoder_id: String,
+    pub decoder: String,
+    // None if the decoder doesn't understand the contents
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GetMessageResult {
+    // decoded with the first matching decoder (like in the messages list)
+    pub message: TopicMessage,
+    pub timestamp: Option<i64>,
+    pub timestamp_type: TimestampType,
+    pub headers: Vec<MessageHeader>,
+    pub raw_format: RawFormat,
+    // the raw key and value are left out when masking rules apply to the topic
+    pub masked: bool,
+    pub raw_key: Option<String>,
+    pub raw_value: Option<String>,
+    pub key_size: Option<usize>,
+    pub value_size: Option<usize>,
+    // the size of the record in the log (without the record batch overhead)
+    pub size: usize,
+    pub decodings: Vec<MessageDecoding>,
 }
 
-#[derive(Serialize, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Serialize, Eq, Ord, PartialEq, PartialOrd, ToSchema)]
 pub struct DecoderMetadata {
     pub display_name: String,
     pub id: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetDecodersResult {
     pub decoders: Vec<DecoderMetadata>
 }
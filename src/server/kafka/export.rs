@@ -0,0 +1,537 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use apache_avro::{Schema, Writer};
+use apache_avro::types::Value as AvroValue;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use rdkafka::Message;
+use rdkafka::message::{Headers, OwnedMessage, Timestamp};
+use rocket::Request;
+use rocket::http::ContentType;
+use rocket::response::{self, Responder, Response};
+use rocket::response::stream::{stream, ByteStream};
+use serde_json::Value;
+use tracing::{debug, error, warn};
+use utoipa::OpenApi;
+
+use crate::auth::guard::User;
+use crate::common::errors::{map_error, ApiError, ErrorResult};
+use crate::kafka::api;
+use crate::kafka::dto;
+use crate::kafka::masking::Masker;
+use crate::kafka::ranges::{parse_partitions, partition_ranges, RangeReader};
+use crate::kafka::search::Search;
+
+// the exported file is sent in chunks of (at least) this size.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+// the csv columns are taken from the messages in the beginning of the export, fields that only appear later are dropped
+// (and listed in a last row of the file).
+const CSV_SAMPLE_SIZE: usize = 1000;
+
+// the maximum number of dropped columns listed.
+const CSV_MAX_DROPPED_COLUMNS: usize = 100;
+
+// The schema of the records in the raw (avro) export, it keeps everything needed for replaying the messages.
+pub const RAW_SCHEMA: &str = r#"{
+    "type": "record",
+    "name": "KafkaMessage",
+    "namespace": "krowser",
+    "fields": [
+        {"name": "topic", "type": "string"},
+        {"name": "partition", "type": "int"},
+        {"name": "offset", "type": "long"},
+        {"name": "timestamp", "type": ["null", "long"]},
+        {"name": "timestamp_type", "type": "string"},
+        {"name": "key", "type": ["null", "bytes"]},
+        {"name": "value", "type": ["null", "bytes"]},
+        {"name": "headers", "type": {"type": "array", "items": {
+            "type": "record",
+            "name": "KafkaHeader",
+            "fields": [
+                {"name": "key", "type": "string"},
+                {"name": "value", "type": ["null", "bytes"]}
+            ]
+        }}}
+    ]
+}"#;
+
+// A file download which is streamed to the client while it is being written.
+pub struct ExportFile {
+    stream: BoxStream<'static, Vec<u8>>,
+    content_type: ContentType,
+    filename: String,
+}
+
+impl<'r> Responder<'r, 'r> for ExportFile {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'r> {
+        Response::build_from(ByteStream(self.stream).respond_to(request)?)
+            .header(self.content_type)
+            .raw_header("Content-Disposition", format!("attachment; filename=\"{}\"", self.filename))
+            .ok()
+    }
+}
+
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, description = "The exported file. CSV columns are taken from the first 1000 messages, fields that first appear later are dropped and listed in a last \"# columns missing from the header\" row. An export that fails midway ends with an error marker: an {\"error\": ...} line (ndjson), an \"ERROR: ...\" row (csv) or an invalid block (avro)", content_type = "application/octet-stream"),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/export/<topic>?<partitions>&<from_offset>&<to_offset>&<from_time>&<to_time>&<limit>&<search>&<search_style>&<decoding>&<format>")]
+pub async fn get_export(
+    topic: &str,
+    partitions: Option<&str>,
+    from_offset: Option<i64>,
+    to_offset: Option<i64>,
+    from_time: Option<i64>,
+    to_time: Option<i64>,
+    limit: Option<u64>,
+    search: Option<&str>,
+    search_style: Option<dto::SearchStyle>,
+    decoding: Option<&str>,
+    format: Option<dto::ExportFormat>,
+    user: User) -> Result<ExportFile, ApiError> {
+    api::check_topic_access(&user, topic)?;
+
+    let format = format.unwrap_or(dto::ExportFormat::Ndjson);
+    let search_style = search_style.unwrap_or(dto::SearchStyle::None);
+    let masker = Masker::new(topic, &user);
+    if format == dto::ExportFormat::Avro && !masker.is_empty() {
+        return Err(ApiError::Forbidden(format!("topic {} has masked fields, it can only be exported decoded (ndjson or csv)", topic)));
+    }
+    let (key_decoders, value_decoders) = api::message_decoders(topic, decoding.unwrap_or(""))?;
+    let partitions = match partitions {
+        None => None,
+        Some(partitions) => Some(parse_partitions(partitions)?),
+    };
+    let ranges = partition_ranges(topic, partitions, from_offset, to_offset, from_time, to_time)?;
+    debug!(partitions = ranges.len(), ?format, ?search, ?search_style, "exporting messages");
+    let search = Search::new(search, search_style)?;
+    let mut reader = RangeReader::new(topic, ranges)?;
+
+    let topic = topic.to_string();
+    let (content_type, extension) = match format {
+        dto::ExportFormat::Ndjson => (ContentType::new("application", "x-ndjson"), "ndjson"),
+        dto::ExportFormat::Csv => (ContentType::CSV, "csv"),
+        dto::ExportFormat::Avro => (ContentType::Binary, "avro"),
+    };
+    let filename = format!("{}.{}", topic, extension);
+
+    let stream = stream! {
+        let mut writer = ExportWriter::new(format);
+        let mut num_exported: u64 = 0;
+        let mut failure = None;
+        while limit.map_or(true, |l| num_exported < l) {
+            let message = match reader.next().await {
+                None if !reader.is_exhausted() => {
+                    failure = Some("the export stopped after being idle (no messages arrived)".to_string());
+                    break;
+                },
+                None => break,
+                Some(message) => message,
+            };
+            let partition = message.partition();
+            let raw = if format == dto::ExportFormat::Avro { Some(message.clone()) } else { None };
+            let parsed = if format == dto::ExportFormat::Avro && search.is_none() {
+                None
+            } else {
+                match api::parse_message(message, partition, search.as_ref(), false, &key_decoders, &value_decoders, &masker).await {
+                    Ok(None) => continue,
+                    Ok(Some(parsed)) => Some(parsed),
+                    Err(e) => {
+                        failure = Some(e.to_string());
+                        break;
+                    }
+                }
+            };
+            let written = match raw {
+                Some(raw) => writer.write_raw(&raw),
+                None => writer.write_decoded(parsed.unwrap()),
+            };
+            if let Err(e) = written {
+                failure = Some(e);
+                break;
+            }
+            num_exported += 1;
+            if writer.buffered() >= CHUNK_SIZE {
+                yield writer.take();
+            }
+        }
+        if let Some(e) = &failure {
+            error!("failed exporting topic {} after {} messages: {}", topic, num_exported, e);
+        }
+        yield writer.finish(failure.as_deref());
+        debug!("exported {} messages from topic {}", num_exported, topic);
+    };
+
+    Ok(ExportFile{
+        stream: stream.boxed(),
+        content_type: content_type,
+        filename: filename,
+    })
+}
+
+// Writes the exported messages to an in-memory buffer which is drained into the response chunk by chunk.
+enum ExportWriter {
+    Ndjson(Vec<u8>),
+    Csv(CsvWriter),
+    Avro(Writer<'static, SharedBuffer>, SharedBuffer),
+}
+
+impl ExportWriter {
+    fn new(format: dto::ExportFormat) -> ExportWriter {
+        match format {
+            dto::ExportFormat::Ndjson => ExportWriter::Ndjson(vec![]),
+            dto::ExportFormat::Csv => ExportWriter::Csv(CsvWriter::default()),
+            dto::ExportFormat::Avro => {
+                let buffer = SharedBuffer::default();
+                ExportWriter::Avro(Writer::new(&RAW_SCHEMA_PARSED, buffer.clone()), buffer)
+            },
+        }
+    }
+
+    fn write_decoded(&mut self, message: dto::TopicMessage) -> Result<(), String> {
+        match self {
+            ExportWriter::Ndjson(buffer) => {
+                map_error(serde_json::to_writer(&mut *buffer, &message))?;
+                buffer.push(b'\n');
+                Ok(())
+            },
+            ExportWriter::Csv(csv) => {
+                csv.write(message);
+                Ok(())
+            },
+            ExportWriter::Avro(_, _) => Err("the avro export only writes raw messages".to_string()),
+        }
+    }
+
+    fn write_raw(&mut self, message: &OwnedMessage) -> Result<(), String> {
+        match self {
+            ExportWriter::Avro(writer, _) => map_error(writer.append(raw_record(message))).map(|_| ()),
+            _ => Err("raw messages are only written in the avro export".to_string()),
+        }
+    }
+
+    fn buffered(&self) -> usize {
+        match self {
+            ExportWriter::Ndjson(buffer) => buffer.len(),
+            ExportWriter::Csv(csv) => csv.buffer.len(),
+            // the avro writer buffers a block internally and writes it out once it is big enough.
+            ExportWriter::Avro(_, buffer) => buffer.len(),
+        }
+    }
+
+    fn take(&mut self) -> Vec<u8> {
+        match self {
+            ExportWriter::Ndjson(buffer) => std::mem::take(buffer),
+            ExportWriter::Csv(csv) => csv.take(),
+            ExportWriter::Avro(_, buffer) => buffer.take(),
+        }
+    }
+
+    // the rest of the file. The response status was sent before the export started, so a failed export ends with
+    // a marker that makes the failure visible: an {"error": ...} line in ndjson, an "ERROR: ..." row in csv and an
+    // invalid block (with the error as its contents) in avro, which avro readers fail on.
+    fn finish(self, failure: Option<&str>) -> Vec<u8> {
+        match self {
+            ExportWriter::Ndjson(mut buffer) => {
+                if let Some(failure) = failure {
+                    buffer.extend_from_slice(serde_json::json!({"error": failure}).to_string().as_bytes());
+                    buffer.push(b'\n');
+                }
+                buffer
+            },
+            ExportWriter::Csv(mut csv) => {
+                csv.flush_sample();
+                csv.write_dropped_columns();
+                let mut buffer = csv.take();
+                if let Some(failure) = failure {
+                    buffer.extend_from_slice(csv_field(&format!("ERROR: the export failed: {}", failure)).as_bytes());
+                    buffer.extend_from_slice(b"\r\n");
+                }
+                buffer
+            },
+            ExportWriter::Avro(writer, buffer) => {
+                let failure = match failure {
+                    // the messages still buffered in the writer are dropped with it.
+                    Some(failure) => Some(failure.to_string()),
+                    None => map_error(writer.into_inner()).err(),
+                };
+                let mut out = buffer.take();
+                if let Some(failure) = failure {
+                    error!("failed finishing an avro export: {}", failure);
+                    out.extend(avro_failure_block(&failure));
+                }
+                out
+            },
+        }
+    }
+}
+
+// a block of a single "record" with the error text and a sync marker that doesn't match the one in the file header.
+fn avro_failure_block(failure: &str) -> Vec<u8> {
+    let message = format!("the export failed: {}", failure);
+    let mut block = vec![];
+    write_long(&mut block, 1);
+    write_long(&mut block, message.len() as i64);
+    block.extend_from_slice(message.as_bytes());
+    block.extend_from_slice(&[0; 16]);
+    block
+}
+
+// avro's zigzag varint encoding of longs.
+fn write_long(out: &mut Vec<u8>, value: i64) {
+    let mut v = ((value << 1) ^ (value >> 63)) as u64;
+    while v >= 0x80 {
+        out.push((v & 0x7f) as u8 | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+pub static RAW_SCHEMA_PARSED: Lazy<Schema> = Lazy::new(|| Schema::parse_str(RAW_SCHEMA).unwrap());
+
+fn raw_record(message: &OwnedMessage) -> AvroValue {
+    let (timestamp, timestamp_type) = match message.timestamp() {
+        Timestamp::NotAvailable => (None, "NotAvailable"),
+        Timestamp::CreateTime(v) => (Some(v), "CreateTime"),
+        Timestamp::LogAppendTime(v) => (Some(v), "LogAppendTime"),
+    };
+    let mut headers = vec![];
+    if let Some(message_headers) = message.headers() {
+        for index in 0..message_headers.count() {
+            if let Some((key, value)) = message_headers.get(index) {
+                headers.push(AvroValue::Record(vec![
+                    ("key".to_string(), AvroValue::String(key.to_string())),
+                    ("value".to_string(), optional_bytes(Some(value))),
+                ]));
+            }
+        }
+    }
+    AvroValue::Record(vec![
+        ("topic".to_string(), AvroValue::String(message.topic().to_string())),
+        ("partition".to_string(), AvroValue::Int(message.partition())),
+        ("offset".to_string(), AvroValue::Long(message.offset())),
+        ("timestamp".to_string(), match timestamp {
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+            Some(v) => AvroValue::Union(1, Box::new(AvroValue::Long(v))),
+        }),
+        ("timestamp_type".to_string(), AvroValue::String(timestamp_type.to_string())),
+        ("key".to_string(), optional_bytes(message.key())),
+        ("value".to_string(), optional_bytes(message.payload())),
+        ("headers".to_string(), AvroValue::Array(headers)),
+    ])
+}
+
+// A message read back from a raw (avro) export.
+pub struct RawMessage {
+    pub partition: i32,
+    pub offset: i64,
+    pub timestamp: Option<i64>,
+    pub key: Option<Vec<u8>>,
+    pub value: Option<Vec<u8>>,
+    pub headers: Vec<(String, Vec<u8>)>,
+}
+
+pub fn read_raw_record(value: AvroValue) -> Result<RawMessage, String> {
+    let mut message = RawMessage{partition: 0, offset: 0, timestamp: None, key: None, value: None, headers: vec![]};
+    let fields = match value {
+        AvroValue::Record(fields) => fields,
+        other => return Err(format!("expected a message record, found: {:?}", other)),
+    };
+    for (name, field) in fields {
+        match (name.as_str(), unwrap_union(field)) {
+            ("partition", AvroValue::Int(v)) => message.partition = v,
+            ("offset", AvroValue::Long(v)) => message.offset = v,
+            ("timestamp", AvroValue::Long(v)) => message.timestamp = Some(v),
+            ("key", AvroValue::Bytes(v)) => message.key = Some(v),
+            ("value", AvroValue::Bytes(v)) => message.value = Some(v),
+            ("headers", AvroValue::Array(headers)) => {
+                for header in headers {
+                    if let AvroValue::Record(header_fields) = header {
+                        let mut key = String::new();
+                        let mut value = vec![];
+                        for (header_name, header_field) in header_fields {
+                            match (header_name.as_str(), unwrap_union(header_field)) {
+                                ("key", AvroValue::String(v)) => key = v,
+                                ("value", AvroValue::Bytes(v)) => value = v,
+                                _ => {},
+                            }
+                        }
+                        message.headers.push((key, value));
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+    Ok(message)
+}
+
+fn unwrap_union(value: AvroValue) -> AvroValue {
+    match value {
+        AvroValue::Union(_, inner) => *inner,
+        other => other,
+    }
+}
+
+fn optional_bytes(bytes: Option<&[u8]>) -> AvroValue {
+    match bytes {
+        None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        Some(v) => AvroValue::Union(1, Box::new(AvroValue::Bytes(v.to_vec()))),
+    }
+}
+
+// A writer target which can still be drained while the avro writer owns it.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+const CSV_FIXED_COLUMNS: [&str; 7] = ["topic", "partition", "offset", "timestamp", "key", "key_decoding", "value_decoding"];
+
+// Writes the messages as csv, with a column for each (nested) field of json values.
+#[derive(Default)]
+struct CsvWriter {
+    columns: Option<Vec<String>>,
+    sample: Vec<Vec<(String, String)>>,
+    // fields that first appeared after the columns were picked
+    dropped: Vec<String>,
+    buffer: Vec<u8>,
+}
+
+impl CsvWriter {
+    fn write(&mut self, message: dto::TopicMessage) {
+        let mut row = vec![
+            ("topic".to_string(), message.topic),
+            ("partition".to_string(), message.partition.to_string()),
+            ("offset".to_string(), message.offset.to_string()),
+            ("timestamp".to_string(), message.timestamp.to_string()),
+            ("key".to_string(), message.key),
+            ("key_decoding".to_string(), message.key_decoding),
+            ("value_decoding".to_string(), message.value_decoding),
+        ];
+        match serde_json::from_str::<Value>(&message.value) {
+            Ok(json) if json.is_object() => flatten("value", &json, &mut row),
+            _ => row.push(("value".to_string(), message.value)),
+        }
+        if self.columns.is_some() {
+            self.write_row(&row);
+            return;
+        }
+        self.sample.push(row);
+        if self.sample.len() >= CSV_SAMPLE_SIZE {
+            self.flush_sample();
+        }
+    }
+
+    // picks the columns from the sampled rows and writes the header and the sampled rows.
+    fn flush_sample(&mut self) {
+        if self.columns.is_some() {
+            return;
+        }
+        let mut columns: Vec<String> = CSV_FIXED_COLUMNS.iter().map(|c| c.to_string()).collect();
+        for row in &self.sample {
+            for (column, _) in row {
+                if !columns.contains(column) {
+                    columns.push(column.to_string());
+                }
+            }
+        }
+        let header = columns.iter().map(|c| csv_field(c)).collect::<Vec<String>>().join(",");
+        self.buffer.extend_from_slice(header.as_bytes());
+        self.buffer.extend_from_slice(b"\r\n");
+        self.columns = Some(columns);
+        for row in std::mem::take(&mut self.sample) {
+            self.write_row(&row);
+        }
+    }
+
+    fn write_row(&mut self, row: &Vec<(String, String)>) {
+        let columns = self.columns.as_ref().unwrap();
+        for (column, _) in row {
+            if self.dropped.len() < CSV_MAX_DROPPED_COLUMNS && !columns.contains(column) && !self.dropped.contains(column) {
+                self.dropped.push(column.to_string());
+            }
+        }
+        let line = columns.iter()
+            .map(|column| match row.iter().find(|(c, _)| c == column) {
+                None => "".to_string(),
+                Some((_, value)) => csv_field(value),
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+        self.buffer.extend_from_slice(line.as_bytes());
+        self.buffer.extend_from_slice(b"\r\n");
+    }
+
+    fn write_dropped_columns(&mut self) {
+        if self.dropped.is_empty() {
+            return;
+        }
+        warn!("dropped csv columns that first appeared after {} messages: {:?}", CSV_SAMPLE_SIZE, self.dropped);
+        let line = format!("# columns missing from the header (first seen after the first {} messages): {}", CSV_SAMPLE_SIZE, self.dropped.join(", "));
+        self.buffer.extend_from_slice(csv_field(&line).as_bytes());
+        self.buffer.extend_from_slice(b"\r\n");
+    }
+
+    fn take(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buffer)
+    }
+}
+
+// flattens nested objects to dotted column names, arrays are kept as json.
+fn flatten(prefix: &str, value: &Value, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                flatten(&format!("{}.{}", prefix, key), child, out);
+            }
+        },
+        Value::String(s) => out.push((prefix.to_string(), s.to_string())),
+        Value::Null => out.push((prefix.to_string(), "".to_string())),
+        other => out.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", value.replace("\"", "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_export,
+    ),
+    components(schemas(
+        dto::ExportFormat,
+    )),
+)]
+pub struct ApiDoc;
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use apache_avro::Reader;
+use rdkafka::message::OwnedHeaders;
+use rdkafka::producer::FutureRecord;
+use regex::Regex;
+use rocket::data::{Data, ToByteUnit};
+use rocket::serde::json::Json;
+use tracing::debug;
+use utoipa::OpenApi;
+
+use crate::config;
+use crate::auth::guard::User;
+use crate::auth::roles::Action;
+use crate::audit::log::Audit;
+use crate::common::errors::{map_error, ApiError, ErrorResult};
+use crate::kafka::api;
+use crate::kafka::dto;
+use crate::kafka::export::{read_raw_record, RAW_SCHEMA_PARSED};
+use crate::kafka::produce::BatchProducer;
+
+#[utoipa::path(
+    tag = "kafka",
+    request_body(content = Vec<u8>, description = "A raw (avro) export file", content_type = "application/octet-stream"),
+    responses(
+        (status = 200, body = dto::ImportResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[post("/api/import/<topic>?<partitioning>&<partition_map>&<timestamps>&<key_filter>&<rate>", data = "<file>")]
+pub async fn import_messages(
+    topic: &str,
+    partitioning: Option<dto::ImportPartitioning>,
+    partition_map: Option<&str>,
+    timestamps: Option<dto::ImportTimestamps>,
+    key_filter: Option<&str>,
+    rate: Option<u32>,
+    file: Data<'_>,
+    user: User,
+    audit: &Audit) -> Result<Json<dto::ImportResult>, ApiError> {
+    if !user.can(Action::Produce) {
+        return Err(ApiError::Forbidden(format!("user {} is not allowed to perform {} actions", user.name, Action::Produce.as_str())));
+    }
+    api::check_topic_access(&user, topic)?;
+
+    let partitioning = partitioning.unwrap_or(dto::ImportPartitioning::Preserve);
+    let timestamps = timestamps.unwrap_or(dto::ImportTimestamps::Keep);
+    let partition_map = parse_partition_map(partition_map)?;
+    if partitioning == dto::ImportPartitioning::Remap && partition_map.is_empty() {
+        return Err(ApiError::BadRequest("remapping partitions requires a partition_map (i.e 0:1,1:0)".to_string()));
+    }
+    let key_filter = match key_filter {
+        None => None,
+        Some(pattern) => Some(Regex::new(pattern).map_err(|e| ApiError::BadRequest(format!("bad key filter regex: {}", e)))?),
+    };
+    if rate == Some(0) {
+        return Err(ApiError::BadRequest("rate must be positive".to_string()));
+    }
+    let partitions = api::get_topic_partitions(topic)?;
+
+    let max_size = (*config::SETTINGS).server.max_import_size_mb;
+    let bytes = map_error(file.open(max_size.mebibytes()).into_bytes().await)?;
+    if !bytes.is_complete() {
+        return Err(ApiError::BadRequest(format!("the file is larger than the maximum import size ({} MB)", max_size)));
+    }
+    let reader = Reader::new(&bytes.value[..]).map_err(|e| ApiError::BadRequest(format!("not an avro file: {}", e)))?;
+    if reader.writer_schema() != &*RAW_SCHEMA_PARSED {
+        return Err(ApiError::BadRequest("the file is not a raw (avro) export".to_string()));
+    }
+
+    let mut producer = BatchProducer::new()?;
+
+    let start = Instant::now();
+    let mut produced: u64 = 0;
+    let mut skipped: u64 = 0;
+    for record in reader {
+        let record = record.map_err(|e| ApiError::BadRequest(format!("failed reading the file: {}", e)))?;
+        let message = read_raw_record(record).map_err(ApiError::BadRequest)?;
+        if let Some(regex) = &key_filter {
+            let key = message.key.as_ref().map(|k| String::from_utf8_lossy(k).to_string()).unwrap_or_default();
+            if !regex.is_match(&key) {
+                skipped += 1;
+                continue;
+            }
+        }
+        let partition = match partitioning {
+            dto::ImportPartitioning::Preserve => Some(message.partition),
+            dto::ImportPartitioning::Remap => Some(*partition_map.get(&message.partition).ok_or(ApiError::BadRequest(
+                format!("partition {} is missing from the partition map", message.partition)))?),
+            dto::ImportPartitioning::Partitioner => None,
+        };
+        if let Some(partition) = partition {
+            if !partitions.contains(&partition) {
+                return Err(ApiError::BadRequest(format!("topic {} has no partition {} (imported {} messages before failing)", topic, partition, produced)));
+            }
+        }
+        let mut headers = OwnedHeaders::new_with_capacity(message.headers.len());
+        for (key, value) in &message.headers {
+            headers = headers.add(key, value);
+        }
+        let mut record: FutureRecord<Vec<u8>, Vec<u8>> = FutureRecord::to(topic).headers(headers);
+        if let Some(key) = &message.key {
+            record = record.key(key);
+        }
+        if let Some(value) = &message.value {
+            record = record.payload(value);
+        }
+        if let Some(partition) = partition {
+            record = record.partition(partition);
+        }
+        if let (dto::ImportTimestamps::Keep, Some(timestamp)) = (timestamps, message.timestamp) {
+            record = record.timestamp(timestamp);
+        }
+        producer.send(record).await?;
+        produced += 1;
+        if let Some(rate) = rate {
+            let due = Duration::from_secs_f64(produced as f64 / rate as f64);
+            let elapsed = start.elapsed();
+            if due > elapsed {
+                tokio::time::sleep(due - elapsed).await;
+            }
+        }
+    }
+    producer.flush().await?;
+    debug!("imported {} messages to topic {} in {:?} (skipped {})", produced, topic, start.elapsed(), skipped);
+    audit.set_result_count(produced as usize);
+
+    Ok(Json(dto::ImportResult{produced: produced, skipped: skipped}))
+}
+
+// parses a partition map in the form of "0:1,1:0" (source partition : target partition).
+fn parse_partition_map(map: Option<&str>) -> Result<HashMap<i32, i32>, ApiError> {
+    let mut out = HashMap::new();
+    let map = match map {
+        None => return Ok(out),
+        Some(map) => map,
+    };
+    for pair in map.split(",").filter(|p| !p.trim().is_empty()) {
+        let bad_pair = || ApiError::BadRequest(format!("bad partition map entry: {}", pair));
+        let (from, to) = pair.split_once(":").ok_or_else(bad_pair)?;
+        let from = from.trim().parse::<i32>().map_err(|_| bad_pair())?;
+        let to = to.trim().parse::<i32>().map_err(|_| bad_pair())?;
+        out.insert(from, to);
+    }
+    Ok(out)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        import_messages,
+    ),
+    components(schemas(
+        dto::ImportPartitioning,
+        dto::ImportTimestamps,
+        dto::ImportResult,
+    )),
+)]
+pub struct ApiDoc;
@@ -0,0 +1,257 @@
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+// A simple subset of JSONPath: dot and bracket notation for fields ($.a.b, $['a']), array indexes ($.a[0])
+// and wildcards ($.a.*, $.a[*]). The leading "$" is optional.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    pub segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<JsonPath, String> {
+        let chars: Vec<char> = path.trim().chars().collect();
+        let mut segments = vec![];
+        let mut i = 0;
+        if chars.first() == Some(&'$') {
+            i += 1;
+        }
+        let mut expect_field = i == 0;
+        while i < chars.len() {
+            match chars[i] {
+                '.' => {
+                    i += 1;
+                    expect_field = true;
+                },
+                '[' => {
+                    let end = chars[i..].iter().position(|c| *c == ']').ok_or(format!("missing ']' in json path {}", path))? + i;
+                    let inner: String = chars[i + 1..end].iter().collect();
+                    let inner = inner.trim();
+                    if inner == "*" {
+                        segments.push(Segment::Wildcard);
+                    } else if (inner.starts_with('\'') && inner.ends_with('\'')) || (inner.starts_with('"') && inner.ends_with('"')) {
+                        if inner.len() < 2 {
+                            return Err(format!("bad field in json path {}", path));
+                        }
+                        segments.push(Segment::Key(inner[1..inner.len() - 1].to_string()));
+                    } else {
+                        let index = inner.parse::<usize>().map_err(|_| format!("bad index '{}' in json path {}", inner, path))?;
+                        segments.push(Segment::Index(index));
+                    }
+                    i = end + 1;
+                    expect_field = false;
+                },
+                _ => {
+                    if !expect_field {
+                        return Err(format!("unexpected '{}' in json path {}", chars[i], path));
+                    }
+                    let end = chars[i..].iter().position(|c| *c == '.' || *c == '[' || *c == ']').map(|p| p + i).unwrap_or(chars.len());
+                    let field: String = chars[i..end].iter().collect();
+                    if field == "*" {
+                        segments.push(Segment::Wildcard);
+                    } else {
+                        segments.push(Segment::Key(field));
+                    }
+                    i = end;
+                    expect_field = false;
+                },
+            }
+        }
+        if chars.last() == Some(&'.') {
+            return Err(format!("json path {} ends with '.'", path));
+        }
+        Ok(JsonPath{segments})
+    }
+
+    // calls the function for every value in the document matched by the path.
+    pub fn for_each_mut(&self, value: &mut Value, f: &mut dyn FnMut(&mut Value)) {
+        visit_mut(&self.segments, value, f);
+    }
+
+    pub fn for_each(&self, value: &Value, f: &mut dyn FnMut(&Value)) {
+        visit(&self.segments, value, f);
+    }
+
+    // returns a document with only the values matched by the path (keeping their nesting), or None if nothing matched.
+    // Array items selected by an index or a wildcard keep their order (but not their original indexes).
+    pub fn select(&self, value: &Value) -> Option<Value> {
+        select(&self.segments, value)
+    }
+}
+
+fn select(segments: &[Segment], value: &Value) -> Option<Value> {
+    let (segment, rest) = match segments.split_first() {
+        None => return Some(value.clone()),
+        Some(split) => split,
+    };
+    match (segment, value) {
+        (Segment::Key(key), Value::Object(map)) => {
+            let child = select(rest, map.get(key)?)?;
+            let mut out = serde_json::Map::new();
+            out.insert(key.to_string(), child);
+            Some(Value::Object(out))
+        },
+        (Segment::Index(index), Value::Array(items)) => Some(Value::Array(vec![select(rest, items.get(*index)?)?])),
+        (Segment::Wildcard, Value::Object(map)) => {
+            let out: serde_json::Map<String, Value> = map.iter()
+                .filter_map(|(key, child)| select(rest, child).map(|v| (key.to_string(), v)))
+                .collect();
+            if out.is_empty() { None } else { Some(Value::Object(out)) }
+        },
+        // items without the selected fields are kept as nulls, so selections of different fields from the same items line up.
+        (Segment::Wildcard, Value::Array(items)) => Some(Value::Array(items.iter().map(|child| select(rest, child).unwrap_or(Value::Null)).collect())),
+        _ => None,
+    }
+}
+
+// merges the selected values of several paths into a single document.
+pub fn merge(target: &mut Value, source: Value) {
+    match (target, source) {
+        (Value::Object(target_map), Value::Object(source_map)) => {
+            for (key, value) in source_map {
+                match target_map.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => { target_map.insert(key, value); },
+                }
+            }
+        },
+        (Value::Array(target_items), Value::Array(source_items)) => {
+            for (index, value) in source_items.into_iter().enumerate() {
+                match target_items.get_mut(index) {
+                    Some(existing) => merge(existing, value),
+                    None => target_items.push(value),
+                }
+            }
+        },
+        (target, source) => {
+            if target.is_null() {
+                *target = source;
+            }
+        },
+    }
+}
+
+fn visit(segments: &[Segment], value: &Value, f: &mut dyn FnMut(&Value)) {
+    let (segment, rest) = match segments.split_first() {
+        None => {
+            f(value);
+            return;
+        },
+        Some(split) => split,
+    };
+    match (segment, value) {
+        (Segment::Key(key), Value::Object(map)) => {
+            if let Some(child) = map.get(key) {
+                visit(rest, child, f);
+            }
+        },
+        (Segment::Index(index), Value::Array(items)) => {
+            if let Some(child) = items.get(*index) {
+                visit(rest, child, f);
+            }
+        },
+        (Segment::Wildcard, Value::Object(map)) => {
+            for (_, child) in map.iter() {
+                visit(rest, child, f);
+            }
+        },
+        (Segment::Wildcard, Value::Array(items)) => {
+            for child in items.iter() {
+                visit(rest, child, f);
+            }
+        },
+        _ => {},
+    }
+}
+
+fn visit_mut(segments: &[Segment], value: &mut Value, f: &mut dyn FnMut(&mut Value)) {
+    let (segment, rest) = match segments.split_first() {
+        None => {
+            f(value);
+            return;
+        },
+        Some(split) => split,
+    };
+    match (segment, value) {
+        (Segment::Key(key), Value::Object(map)) => {
+            if let Some(child) = map.get_mut(key) {
+                visit_mut(rest, child, f);
+            }
+        },
+        (Segment::Index(index), Value::Array(items)) => {
+            if let Some(child) = items.get_mut(*index) {
+                visit_mut(rest, child, f);
+            }
+        },
+        (Segment::Wildcard, Value::Object(map)) => {
+            for (_, child) in map.iter_mut() {
+                visit_mut(rest, child, f);
+            }
+        },
+        (Segment::Wildcard, Value::Array(items)) => {
+            for child in items.iter_mut() {
+                visit_mut(rest, child, f);
+            }
+        },
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn key(k: &str) -> Segment {
+        Segment::Key(k.to_string())
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(JsonPath::parse("$.a.b").unwrap().segments, vec![key("a"), key("b")]);
+        assert_eq!(JsonPath::parse("a.b").unwrap().segments, vec![key("a"), key("b")]);
+        assert_eq!(JsonPath::parse("$['a b'][\"c\"]").unwrap().segments, vec![key("a b"), key("c")]);
+        assert_eq!(JsonPath::parse("$.items[2].id").unwrap().segments, vec![key("items"), Segment::Index(2), key("id")]);
+        assert_eq!(JsonPath::parse("$.a.*").unwrap().segments, vec![key("a"), Segment::Wildcard]);
+        assert_eq!(JsonPath::parse("$.a[*].b").unwrap().segments, vec![key("a"), Segment::Wildcard, key("b")]);
+        assert_eq!(JsonPath::parse("$").unwrap().segments, vec![]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        for path in ["$.a[", "$.a[x]", "$.a[-1]", "$.a.", "$a", "$.a]", "$['']x"] {
+            assert!(JsonPath::parse(path).is_err(), "{} should fail", path);
+        }
+    }
+
+    #[test]
+    fn for_each() {
+        let doc = json!({"a": {"b": 1, "c": 2}, "items": [{"id": 1}, {"id": 2}, {"name": "x"}]});
+        let mut found = vec![];
+        JsonPath::parse("$.items[*].id").unwrap().for_each(&doc, &mut |v| found.push(v.clone()));
+        assert_eq!(found, vec![json!(1), json!(2)]);
+        found.clear();
+        JsonPath::parse("$.a.*").unwrap().for_each(&doc, &mut |v| found.push(v.clone()));
+        assert_eq!(found, vec![json!(1), json!(2)]);
+        found.clear();
+        JsonPath::parse("$.items[1].id").unwrap().for_each(&doc, &mut |v| found.push(v.clone()));
+        assert_eq!(found, vec![json!(2)]);
+        found.clear();
+        JsonPath::parse("$.missing.id").unwrap().for_each(&doc, &mut |v| found.push(v.clone()));
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn for_each_mut() {
+        let mut doc = json!({"items": [{"id": 1}, {"id": 2}], "id": 3});
+        JsonPath::parse("$.items[*].id").unwrap().for_each_mut(&mut doc, &mut |v| *v = json!("x"));
+        assert_eq!(doc, json!({"items": [{"id": "x"}, {"id": "x"}], "id": 3}));
+    }
+}
@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use rdkafka::Message;
+use rocket::serde::json::Json;
+use tokio::time::{timeout_at, Instant};
+use tracing::debug;
+use utoipa::OpenApi;
+
+use crate::auth::guard::User;
+use crate::audit::log::Audit;
+use crate::common::errors::{ApiError, ErrorResult};
+use crate::kafka::api;
+use crate::kafka::dto;
+use crate::kafka::masking::Masker;
+use crate::kafka::partitioners::key_partitions;
+use crate::kafka::ranges::{PartitionRange, RangeReader};
+
+// the partition is read backwards in chunks, starting small since the key was usually produced recently.
+const FIRST_CHUNK_SIZE: i64 = 100;
+const MAX_CHUNK_SIZE: i64 = 10000;
+
+const DEFAULT_MAX_SCAN: i64 = 100000;
+
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::KeyLookupResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/key/<topic>?<key>&<partitioner>&<versions>&<max_scan>&<timeout_millis>&<decoding>")]
+pub async fn get_key_versions(
+    topic: &str,
+    key: &str,
+    partitioner: Option<dto::Partitioner>,
+    versions: Option<usize>,
+    max_scan: Option<i64>,
+    timeout_millis: Option<u64>,
+    decoding: Option<&str>,
+    user: User,
+    audit: &Audit) -> Result<Json<dto::KeyLookupResult>, ApiError> {
+    api::check_topic_access(&user, topic)?;
+
+    let partitioner = partitioner.unwrap_or(dto::Partitioner::Murmur2);
+    let versions = versions.unwrap_or(1).max(1);
+    let max_scan = max_scan.unwrap_or(DEFAULT_MAX_SCAN);
+    let deadline = Instant::now() + Duration::from_millis(timeout_millis.unwrap_or(20000));
+    let (key_decoders, value_decoders) = api::message_decoders(topic, decoding.unwrap_or(""))?;
+    let masker = Masker::new(topic, &user);
+    let partitions = key_partitions(partitioner, key.as_bytes(), api::get_topic_partitions(topic)?.len() as i32);
+    debug!(?partitioner, ?partitions, versions, max_scan, "looking up key");
+
+    let mut reader = RangeReader::new(topic, vec![])?;
+    let mut found = vec![];
+    let mut scanned = 0;
+    let mut has_timeout = false;
+    for partition in &partitions {
+        let offsets = api::get_partition_offsets(topic, *partition)?;
+        let mut end = offsets.high;
+        let mut chunk_size = FIRST_CHUNK_SIZE;
+        let mut partition_found = vec![];
+        while end > offsets.low && partition_found.len() < versions && scanned < max_scan && !has_timeout {
+            let from = (end - chunk_size).max(offsets.low);
+            reader.reset(vec![PartitionRange{partition: *partition, from: from, to: end - 1}])?;
+            let mut chunk_found = vec![];
+            loop {
+                match timeout_at(deadline, reader.next()).await {
+                    Err(_) => {
+                        has_timeout = true;
+                        break;
+                    },
+                    Ok(None) => break,
+                    Ok(Some(message)) => {
+                        scanned += 1;
+                        if message.key() == Some(key.as_bytes()) {
+                            chunk_found.push(message);
+                        }
+                    },
+                }
+            }
+            // the chunk is read forwards, so its newest matches are the last ones.
+            partition_found.extend(chunk_found.into_iter().rev());
+            end = from;
+            chunk_size = (chunk_size * 2).min(MAX_CHUNK_SIZE);
+        }
+        partition_found.truncate(versions);
+        found.extend(partition_found);
+    }
+    found.sort_by_key(|m| std::cmp::Reverse(m.timestamp().to_millis().unwrap_or(0)));
+    found.truncate(versions);
+
+    let mut messages = Vec::with_capacity(found.len());
+    for message in found {
+        let partition = message.partition();
+        if let Some(message) = api::parse_message(message, partition, None, false, &key_decoders, &value_decoders, &masker).await? {
+            messages.push(message);
+        }
+    }
+    audit.set_result_count(messages.len());
+    Ok(Json(dto::KeyLookupResult{
+        partitions: partitions,
+        messages: messages,
+        scanned: scanned,
+        has_timeout: has_timeout,
+    }))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_key_versions,
+    ),
+    components(schemas(
+        dto::Partitioner,
+        dto::KeyLookupResult,
+        dto::TopicMessage,
+    )),
+)]
+pub struct ApiDoc;
@@ -0,0 +1,219 @@
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+use sha2::Sha256;
+
+use crate::config;
+use crate::auth::guard::User;
+use crate::kafka::json_path::JsonPath;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MaskAction {
+    Redact,
+    Hash,
+    Partial,
+}
+
+pub struct MaskingRule {
+    topics: Regex,
+    json_paths: Vec<JsonPath>,
+    regexes: Vec<Regex>,
+    action: MaskAction,
+    exempt_roles: Vec<String>,
+    hash_key: String,
+}
+
+static RULES: Lazy<Vec<MaskingRule>> = Lazy::new(|| compile_rules().unwrap());
+
+pub fn check_config() -> Result<(), String> {
+    compile_rules().map(|_| ())
+}
+
+fn compile_rules() -> Result<Vec<MaskingRule>, String> {
+    let masking = &(*config::SETTINGS).masking;
+    let rules = match &masking.rules {
+        None => return Ok(vec![]),
+        Some(rules) => rules,
+    };
+    let mut out = Vec::with_capacity(rules.len());
+    for rule in rules {
+        let action = match rule.action.as_str() {
+            "redact" => MaskAction::Redact,
+            "hash" => MaskAction::Hash,
+            "partial" => MaskAction::Partial,
+            action => return Err(format!("unknown masking action: {}", action)),
+        };
+        // without a secret key, the hashes of guessable values (i.e emails or card numbers) could be reversed.
+        if action == MaskAction::Hash && masking.hash_key.is_empty() {
+            return Err("a masking rule uses the hash action but no masking hash-key is configured".to_string());
+        }
+        out.push(MaskingRule{
+            topics: Regex::new(&format!("^(?:{})$", rule.topics)).map_err(|e| format!("bad masking topics pattern {}: {}", rule.topics, e))?,
+            json_paths: rule.json_paths.iter().map(|p| JsonPath::parse(p)).collect::<Result<Vec<JsonPath>, String>>()?,
+            regexes: rule.regexes.iter().map(|r| Regex::new(r).map_err(|e| format!("bad masking regex {}: {}", r, e))).collect::<Result<Vec<Regex>, String>>()?,
+            action: action,
+            exempt_roles: rule.exempt_roles.to_vec(),
+            hash_key: masking.hash_key.to_string(),
+        });
+    }
+    Ok(out)
+}
+
+// Masks the decoded keys/values of a topic with the rules that apply to it for the given user.
+// It is created once per request.
+pub struct Masker {
+    rules: Vec<&'static MaskingRule>,
+}
+
+impl Masker {
+    pub fn new(topic: &str, user: &User) -> Masker {
+        Masker::with_rules(&RULES, topic, &user.role_names())
+    }
+
+    fn with_rules(rules: &'static [MaskingRule], topic: &str, roles: &[String]) -> Masker {
+        Masker {
+            rules: rules.iter()
+                .filter(|rule| rule.topics.is_match(topic))
+                .filter(|rule| !rule.exempt_roles.iter().any(|r| roles.contains(r)))
+                .collect(),
+        }
+    }
+
+    // true if there are no rules to apply, i.e the raw contents of the topic can be shown to the user.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn mask(&self, text: String) -> String {
+        if self.rules.is_empty() {
+            return text;
+        }
+        match serde_json::from_str::<Value>(&text) {
+            Ok(mut json) if json.is_object() || json.is_array() => {
+                for rule in &self.rules {
+                    for path in &rule.json_paths {
+                        path.for_each_mut(&mut json, &mut |value| {
+                            *value = Value::String(apply(rule, &value_text(value)));
+                        });
+                    }
+                    if !rule.regexes.is_empty() {
+                        mask_strings(&mut json, rule);
+                    }
+                }
+                serde_json::to_string(&json).unwrap_or(text)
+            },
+            // not a json document, so only the regexes can be applied.
+            _ => {
+                let mut text = text;
+                for rule in &self.rules {
+                    text = mask_regexes(&text, rule);
+                }
+                text
+            }
+        }
+    }
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn mask_strings(value: &mut Value, rule: &MaskingRule) {
+    match value {
+        Value::String(s) => *s = mask_regexes(s, rule),
+        Value::Array(items) => items.iter_mut().for_each(|item| mask_strings(item, rule)),
+        Value::Object(map) => map.iter_mut().for_each(|(_, item)| mask_strings(item, rule)),
+        _ => {},
+    }
+}
+
+fn mask_regexes(text: &str, rule: &MaskingRule) -> String {
+    let mut out = text.to_string();
+    for regex in &rule.regexes {
+        out = regex.replace_all(&out, |caps: &regex::Captures| apply(rule, &caps[0])).to_string();
+    }
+    out
+}
+
+fn apply(rule: &MaskingRule, text: &str) -> String {
+    match rule.action {
+        MaskAction::Redact => "[REDACTED]".to_string(),
+        MaskAction::Hash => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(rule.hash_key.as_bytes()).expect("hmac accepts keys of any size");
+            mac.update(text.as_bytes());
+            let digest = mac.finalize().into_bytes();
+            format!("hmac-sha256:{}", digest.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+        },
+        // keeps the last 4 characters visible (unless the text is too short for that to be safe).
+        MaskAction::Partial => {
+            let chars: Vec<char> = text.chars().collect();
+            let visible = if chars.len() > 8 { 4 } else { 0 };
+            chars.iter().enumerate()
+                .map(|(i, c)| if i < chars.len() - visible { '*' } else { *c })
+                .collect()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(action: MaskAction, json_paths: &[&str], regexes: &[&str]) -> MaskingRule {
+        MaskingRule{
+            topics: Regex::new("^(?:customers.*)$").unwrap(),
+            json_paths: json_paths.iter().map(|p| JsonPath::parse(p).unwrap()).collect(),
+            regexes: regexes.iter().map(|r| Regex::new(r).unwrap()).collect(),
+            action: action,
+            exempt_roles: vec!["pii-readers".to_string()],
+            hash_key: "Jefe".to_string(),
+        }
+    }
+
+    fn masker(rules: Vec<MaskingRule>, roles: &[&str]) -> Masker {
+        let rules: &'static [MaskingRule] = Box::leak(rules.into_boxed_slice());
+        Masker::with_rules(rules, "customers", &roles.iter().map(|r| r.to_string()).collect::<Vec<String>>())
+    }
+
+    #[test]
+    fn redact() {
+        let masker = masker(vec![rule(MaskAction::Redact, &["$.email"], &[])], &[]);
+        assert_eq!(masker.mask(r#"{"email":"a@b.com","name":"alice"}"#.to_string()), r#"{"email":"[REDACTED]","name":"alice"}"#);
+        let masker = self::masker(vec![rule(MaskAction::Redact, &[], &[r"\d{4}-\d{4}"])], &[]);
+        assert_eq!(masker.mask(r#"{"card":"card 1234-5678"}"#.to_string()), r#"{"card":"card [REDACTED]"}"#);
+        assert_eq!(masker.mask("card 1234-5678".to_string()), "card [REDACTED]");
+    }
+
+    #[test]
+    fn hash_with_the_key() {
+        // the hmac-sha256 test vector with the key "Jefe" (rfc 4231, test case 2).
+        let expected = "hmac-sha256:5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+        let masker = masker(vec![rule(MaskAction::Hash, &["$.question"], &[])], &[]);
+        assert_eq!(masker.mask(r#"{"question":"what do ya want for nothing?"}"#.to_string()), format!(r#"{{"question":"{}"}}"#, expected));
+        let masker = self::masker(vec![rule(MaskAction::Hash, &[], &["what.*nothing\\?"])], &[]);
+        assert_eq!(masker.mask("what do ya want for nothing?".to_string()), expected);
+    }
+
+    #[test]
+    fn partial() {
+        let masker = masker(vec![rule(MaskAction::Partial, &["$.cards[*].number"], &[])], &[]);
+        assert_eq!(
+            masker.mask(r#"{"cards":[{"number":"1234567890123456"},{"number":"12345678"}]}"#.to_string()),
+            r#"{"cards":[{"number":"************3456"},{"number":"********"}]}"#);
+        let masker = self::masker(vec![rule(MaskAction::Partial, &[], &[r"\d{9,}"])], &[]);
+        assert_eq!(masker.mask("phone 0501234567".to_string()), "phone ******4567");
+    }
+
+    #[test]
+    fn exempt_roles() {
+        let masker = masker(vec![rule(MaskAction::Redact, &["$.email"], &[])], &["pii-readers"]);
+        assert!(masker.is_empty());
+        assert_eq!(masker.mask(r#"{"email":"a@b.com"}"#.to_string()), r#"{"email":"a@b.com"}"#);
+        let masker = self::masker(vec![rule(MaskAction::Redact, &["$.email"], &[])], &["support"]);
+        assert!(!masker.is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use rdkafka::Message;
+use rdkafka::message::{Headers, OwnedMessage, Timestamp};
+use rocket::serde::json::Json;
+use serverapi::{Decoder, DecodingAttribute};
+use tokio::time::timeout;
+use utoipa::OpenApi;
+
+use crate::auth::guard::User;
+use crate::audit::log::Audit;
+use crate::common::errors::{ApiError, ErrorResult};
+use crate::kafka::api;
+use crate::kafka::dto;
+use crate::kafka::masking::Masker;
+use crate::kafka::ranges::{PartitionRange, RangeReader};
+
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::GetMessageResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/message/<topic>/<partition>/<offset>?<decoding>&<raw_format>&<timeout_millis>")]
+pub async fn get_message(
+    topic: &str,
+    partition: i32,
+    offset: i64,
+    decoding: Option<&str>,
+    raw_format: Option<dto::RawFormat>,
+    timeout_millis: Option<u64>,
+    user: User,
+    audit: &Audit) -> Result<Json<dto::GetMessageResult>, ApiError> {
+    api::check_topic_access(&user, topic)?;
+
+    let raw_format = raw_format.unwrap_or(dto::RawFormat::Base64);
+    let (key_decoders, value_decoders) = api::message_decoders(topic, decoding.unwrap_or(""))?;
+    let masker = Masker::new(topic, &user);
+    let offsets = api::get_partition_offsets(topic, partition)?;
+    if offset < offsets.low || offset >= offsets.high {
+        return Err(ApiError::NotFound(format!("offset {} of topic {} partition {} not found (the partition has offsets {} to {})", offset, topic, partition, offsets.low, offsets.high - 1)));
+    }
+    let mut reader = RangeReader::new(topic, vec![PartitionRange{partition: partition, from: offset, to: offset}])?;
+    let message = match timeout(Duration::from_millis(timeout_millis.unwrap_or(20000)), reader.next()).await {
+        Err(_) => return Err(ApiError::Timeout(format!("timed out reading offset {} of topic {} partition {}", offset, topic, partition))),
+        // compacted topics (and transaction markers) have offsets without messages.
+        Ok(None) => return Err(ApiError::NotFound(format!("offset {} of topic {} partition {} not found", offset, topic, partition))),
+        Ok(Some(message)) => message,
+    };
+
+    let mut decodings = all_decodings(&message, DecodingAttribute::Key, &key_decoders, &masker).await;
+    decodings.extend(all_decodings(&message, DecodingAttribute::Value, &value_decoders, &masker).await);
+    let (timestamp, timestamp_type) = match message.timestamp() {
+        Timestamp::NotAvailable => (None, dto::TimestampType::NotAvailable),
+        Timestamp::CreateTime(v) => (Some(v), dto::TimestampType::CreateTime),
+        Timestamp::LogAppendTime(v) => (Some(v), dto::TimestampType::LogAppendTime),
+    };
+    // the headers can hold the same sensitive data as the value, so they are masked as well.
+    let masked = !masker.is_empty();
+    let mut headers = vec![];
+    if let Some(message_headers) = message.headers() {
+        for index in 0..message_headers.count() {
+            if let Some((key, value)) = message_headers.get(index) {
+                headers.push(dto::MessageHeader{
+                    key: key.to_string(),
+                    value: masker.mask(String::from_utf8_lossy(value).to_string()),
+                    raw: if masked { None } else { Some(encode(value, raw_format)) },
+                });
+            }
+        }
+    }
+    let raw = |bytes: Option<&[u8]>| if masked { None } else { bytes.map(|b| encode(b, raw_format)) };
+    let (raw_key, raw_value) = (raw(message.key()), raw(message.payload()));
+    let (key_size, value_size, size) = (message.key().map(|k| k.len()), message.payload().map(|v| v.len()), record_size(&message));
+
+    let decoded = api::parse_message(message, partition, None, false, &key_decoders, &value_decoders, &masker).await?;
+    let decoded = decoded.ok_or(ApiError::NotFound(format!("offset {} of topic {} partition {} not found", offset, topic, partition)))?;
+    audit.set_offsets(offset, offset);
+    audit.set_result_count(1);
+    Ok(Json(dto::GetMessageResult{
+        message: decoded,
+        timestamp: timestamp,
+        timestamp_type: timestamp_type,
+        headers: headers,
+        raw_format: raw_format,
+        masked: masked,
+        raw_key: raw_key,
+        raw_value: raw_value,
+        key_size: key_size,
+        value_size: value_size,
+        size: size,
+        decodings: decodings,
+    }))
+}
+
+// the results of all of the applicable decoders (and not just the first that matches).
+async fn all_decodings(message: &OwnedMessage, attr: DecodingAttribute, decoders: &Vec<&Box<dyn Decoder>>, masker: &Masker) -> Vec<dto::MessageDecoding> {
+    let attribute = match attr {
+        DecodingAttribute::Key => "key",
+        DecodingAttribute::Value => "value",
+    };
+    let mut decodings = Vec::with_capacity(decoders.len());
+    for decoder in decoders {
+        let (result, error) = match decoder.decode(message, &attr).await {
+            Ok(contents) => (contents.json.map(|json| masker.mask(json)), None),
+            // decoding errors might quote parts of the message.
+            Err(e) => (None, Some(masker.mask(e))),
+        };
+        decodings.push(dto::MessageDecoding{
+            attribute: attribute.to_string(),
+            decoder_id: decoder.id().to_string(),
+            decoder: decoder.display_name().to_string(),
+            result: result,
+            error: error,
+        });
+    }
+    decodings
+}
+
+fn encode(bytes: &[u8], format: dto::RawFormat) -> String {
+    match format {
+        dto::RawFormat::Base64 => base64::encode(bytes),
+        dto::RawFormat::Hex => hex_dump(bytes),
+    }
+}
+
+// 16 bytes per line: the offset, the bytes in hex and the printable ascii characters.
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 4 + 16);
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        let hex: Vec<String> = (0..16).map(|i| chunk.get(i).map_or("  ".to_string(), |b| format!("{:02x}", b))).collect();
+        let ascii: String = chunk.iter().map(|b| if b.is_ascii_graphic() || *b == b' ' { *b as char } else { '.' }).collect();
+        out.push_str(&format!("{:08x}  {}  {}  |{}|\n", line * 16, hex[..8].join(" "), hex[8..].join(" "), ascii));
+    }
+    out
+}
+
+// the size of the record in the v2 (record batch) format. The timestamp and offset deltas depend on the batch, which
+// is unknown here, so they are counted as single bytes.
+fn record_size(message: &OwnedMessage) -> usize {
+    let bytes = |b: Option<&[u8]>| match b {
+        None => varint_size(-1),
+        Some(b) => varint_size(b.len() as i64) + b.len(),
+    };
+    let mut size = 1 + varint_size(0) + varint_size(0) + bytes(message.key()) + bytes(message.payload());
+    let count = message.headers().map_or(0, |h| h.count());
+    size += varint_size(count as i64);
+    if let Some(headers) = message.headers() {
+        for index in 0..count {
+            if let Some((key, value)) = headers.get(index) {
+                size += bytes(Some(key.as_bytes())) + bytes(Some(value));
+            }
+        }
+    }
+    size + varint_size(size as i64)
+}
+
+// the size of a zigzag encoded varint.
+fn varint_size(value: i64) -> usize {
+    let mut v = ((value << 1) ^ (value >> 63)) as u64;
+    let mut size = 1;
+    while v >= 0x80 {
+        v >>= 7;
+        size += 1;
+    }
+    size
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_message,
+    ),
+    components(schemas(
+        dto::GetMessageResult,
+        dto::TopicMessage,
+        dto::TimestampType,
+        dto::RawFormat,
+        dto::MessageHeader,
+        dto::MessageDecoding,
+    )),
+)]
+pub struct ApiDoc;
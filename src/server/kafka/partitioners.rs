@@ -0,0 +1,96 @@
+use crate::kafka::dto::Partitioner;
+
+// the partitions a message with the key could have been produced to (all of them when the partitioner picks a random one).
+pub fn key_partitions(partitioner: Partitioner, key: &[u8], partitions: i32) -> Vec<i32> {
+    if partitions <= 0 {
+        return vec![];
+    }
+    let partition = match partitioner {
+        Partitioner::Murmur2 => (murmur2(key) & 0x7fffffff) % partitions,
+        Partitioner::Crc32 => (crc32(key) % partitions as u32) as i32,
+        Partitioner::ConsistentRandom if key.is_empty() => return (0..partitions).collect(),
+        Partitioner::ConsistentRandom => (crc32(key) % partitions as u32) as i32,
+    };
+    vec![partition]
+}
+
+// the murmur2 hash of the java client (org.apache.kafka.common.utils.Utils.murmur2).
+fn murmur2(data: &[u8]) -> i32 {
+    const SEED: u32 = 0x9747b28c;
+    const M: u32 = 0x5bd1e995;
+    const R: u32 = 24;
+
+    let mut h: u32 = SEED ^ data.len() as u32;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+        h ^= k;
+    }
+    let tail = chunks.remainder();
+    if tail.len() >= 3 {
+        h ^= (tail[2] as u32) << 16;
+    }
+    if tail.len() >= 2 {
+        h ^= (tail[1] as u32) << 8;
+    }
+    if !tail.is_empty() {
+        h ^= tail[0] as u32;
+        h = h.wrapping_mul(M);
+    }
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^= h >> 15;
+    h as i32
+}
+
+// crc32 (IEEE), as used by librdkafka's partitioners.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xffffffff;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb88320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn murmur2_matches_the_java_client() {
+        // from the java client's UtilsTest.testMurmur2
+        assert_eq!(murmur2("21".as_bytes()), -973932308);
+        assert_eq!(murmur2("foobar".as_bytes()), -790332482);
+        assert_eq!(murmur2("a-little-bit-long-string".as_bytes()), -985981536);
+        assert_eq!(murmur2("a-little-bit-longer-string".as_bytes()), -1486304829);
+        assert_eq!(murmur2("lkjh234lh9fiuh90y23oiuhsafujhadof229phr9h19h89h8".as_bytes()), -58897971);
+        assert_eq!(murmur2(&[b'a', b'b', b'c']), 479470107);
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32("123456789".as_bytes()), 0xCBF43926);
+        assert_eq!(crc32(&[]), 0);
+    }
+
+    #[test]
+    fn partition_selection() {
+        // murmur2("21") is -973932308, its positive part is 1173551340.
+        assert_eq!(key_partitions(Partitioner::Murmur2, "21".as_bytes(), 3), vec![0]);
+        assert_eq!(key_partitions(Partitioner::Murmur2, "21".as_bytes(), 7), vec![1173551340 % 7]);
+        assert_eq!(key_partitions(Partitioner::Murmur2, "21".as_bytes(), 1), vec![0]);
+        // crc32("123456789") is 3421780262.
+        assert_eq!(key_partitions(Partitioner::Crc32, "123456789".as_bytes(), 4), vec![2]);
+        assert_eq!(key_partitions(Partitioner::Crc32, "123456789".as_bytes(), 10), vec![2]);
+        assert_eq!(key_partitions(Partitioner::ConsistentRandom, "123456789".as_bytes(), 10), vec![2]);
+        assert_eq!(key_partitions(Partitioner::ConsistentRandom, &[], 3), vec![0, 1, 2]);
+        assert_eq!(key_partitions(Partitioner::Murmur2, "21".as_bytes(), 0), Vec::<i32>::new());
+    }
+}
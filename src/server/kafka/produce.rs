@@ -0,0 +1,58 @@
+use futures::future::join_all;
+use rdkafka::config::ClientConfig;
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::message::ToBytes;
+use rdkafka::producer::{DeliveryFuture, FutureProducer, FutureRecord};
+
+use crate::config;
+use crate::common::errors::{retry, ApiError};
+
+// the number of messages that are sent before waiting for their delivery reports.
+const MAX_IN_FLIGHT: usize = 1000;
+
+// Produces messages without waiting for each message to be delivered, the delivery reports are checked in batches.
+pub struct BatchProducer {
+    producer: FutureProducer,
+    pending: Vec<DeliveryFuture>,
+}
+
+impl BatchProducer {
+    pub fn new() -> Result<BatchProducer, ApiError> {
+        let producer: FutureProducer = retry("connecting producer", &mut || ClientConfig::new()
+            .set("bootstrap.servers", &(*config::SETTINGS).kafka.urls)
+            .create()).map_err(ApiError::KafkaUnavailable)?;
+        Ok(BatchProducer{producer: producer, pending: Vec::with_capacity(MAX_IN_FLIGHT)})
+    }
+
+    pub async fn send<K: ToBytes + ?Sized, P: ToBytes + ?Sized>(&mut self, mut record: FutureRecord<'_, K, P>) -> Result<(), ApiError> {
+        loop {
+            match self.producer.send_result(record) {
+                Ok(delivery) => {
+                    self.pending.push(delivery);
+                    break;
+                },
+                Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), returned)) => {
+                    record = returned;
+                    self.flush().await?;
+                },
+                Err((e, _)) => return Err(ApiError::KafkaUnavailable(format!("failed producing message: {}", e))),
+            }
+        }
+        if self.pending.len() >= MAX_IN_FLIGHT {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    // waits for all of the sent messages to be delivered.
+    pub async fn flush(&mut self) -> Result<(), ApiError> {
+        for result in join_all(self.pending.drain(..)).await {
+            match result {
+                Err(_) => return Err(ApiError::KafkaUnavailable("the producer was closed before delivering the messages".to_string())),
+                Ok(Err((e, _))) => return Err(ApiError::KafkaUnavailable(format!("failed delivering message: {}", e))),
+                Ok(Ok(_)) => {},
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use serde_json::Value;
+
+use crate::common::errors::ApiError;
+use crate::kafka::json_path::{merge, JsonPath};
+
+// Projects the decoded keys and values to the requested fields only. The fields are json paths which start with
+// "key" or "value" (i.e value.order.status, value.items[*].id), a key/value without requested fields is kept as is.
+pub struct Projection {
+    key_paths: Vec<JsonPath>,
+    value_paths: Vec<JsonPath>,
+}
+
+impl Projection {
+    // returns None when no fields were requested.
+    pub fn new(fields: &Vec<&str>) -> Result<Option<Projection>, ApiError> {
+        if fields.is_empty() {
+            return Ok(None);
+        }
+        let mut projection = Projection{key_paths: vec![], value_paths: vec![]};
+        for field in fields {
+            let field = field.trim();
+            let (paths, rest) = if let Some(rest) = field.strip_prefix("value") {
+                (&mut projection.value_paths, rest)
+            } else if let Some(rest) = field.strip_prefix("key") {
+                (&mut projection.key_paths, rest)
+            } else {
+                return Err(ApiError::BadRequest(format!("fields must start with \"key\" or \"value\", found: {}", field)));
+            };
+            if !rest.is_empty() && !rest.starts_with('.') && !rest.starts_with('[') {
+                return Err(ApiError::BadRequest(format!("fields must start with \"key\" or \"value\", found: {}", field)));
+            }
+            paths.push(JsonPath::parse(rest).map_err(ApiError::BadRequest)?);
+        }
+        Ok(Some(projection))
+    }
+
+    pub fn project_key(&self, key: String) -> String {
+        project(&self.key_paths, key)
+    }
+
+    pub fn project_value(&self, value: String) -> String {
+        project(&self.value_paths, value)
+    }
+}
+
+fn project(paths: &Vec<JsonPath>, text: String) -> String {
+    if paths.is_empty() {
+        return text;
+    }
+    let json = match serde_json::from_str::<Value>(&text) {
+        Ok(json) if json.is_object() || json.is_array() => json,
+        _ => return text,
+    };
+    let mut out = Value::Null;
+    for path in paths {
+        if let Some(selected) = path.select(&json) {
+            merge(&mut out, selected);
+        }
+    }
+    if out.is_null() {
+        out = Value::Object(serde_json::Map::new());
+    }
+    serde_json::to_string(&out).unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn project_value(fields: Vec<&str>, value: Value) -> Value {
+        let projection = Projection::new(&fields).unwrap().unwrap();
+        serde_json::from_str(&projection.project_value(value.to_string())).unwrap()
+    }
+
+    #[test]
+    fn fields() {
+        let value = json!({"order": {"status": "new", "total": 5}, "items": [{"id": 1, "name": "a"}, {"id": 2}]});
+        assert_eq!(project_value(vec!["value.order.status"], value.clone()), json!({"order": {"status": "new"}}));
+        assert_eq!(project_value(vec!["value.order.status", "value.items[*].id"], value.clone()),
+            json!({"order": {"status": "new"}, "items": [{"id": 1}, {"id": 2}]}));
+        assert_eq!(project_value(vec!["value"], value.clone()), value);
+    }
+
+    #[test]
+    fn missing_fields() {
+        let value = json!({"order": {"status": "new"}, "items": [{"id": 1, "name": "a"}, {"id": 2}]});
+        assert_eq!(project_value(vec!["value.missing"], value.clone()), json!({}));
+        assert_eq!(project_value(vec!["value.order.missing", "value.order.status"], value.clone()), json!({"order": {"status": "new"}}));
+        // items without the field are kept as nulls, so the items of several fields line up.
+        assert_eq!(project_value(vec!["value.items[*].name"], value.clone()), json!({"items": [{"name": "a"}, null]}));
+        assert_eq!(project_value(vec!["value.items[5]"], value), json!({}));
+    }
+
+    #[test]
+    fn keys_and_non_json() {
+        let projection = Projection::new(&vec!["key.id"]).unwrap().unwrap();
+        assert_eq!(projection.project_key(r#"{"id":1,"region":"eu"}"#.to_string()), r#"{"id":1}"#);
+        // values without requested fields, and values which aren't json documents, are kept as is.
+        assert_eq!(projection.project_value(r#"{"a":1}"#.to_string()), r#"{"a":1}"#);
+        assert_eq!(projection.project_key("plain text".to_string()), "plain text");
+    }
+
+    #[test]
+    fn bad_fields() {
+        assert!(Projection::new(&vec![]).unwrap().is_none());
+        for field in ["order.status", "values.a", "value.a[", "key."] {
+            assert!(Projection::new(&vec![field]).is_err(), "{} should fail", field);
+        }
+    }
+}
@@ -0,0 +1,402 @@
+use regex::Regex;
+use serde_json::Value;
+
+use crate::kafka::json_path::JsonPath;
+
+// A structured query over the decoded key and value of a message, i.e:
+//   value.order.status == "FAILED" && (value.amount > 100 || key =~ "^vip-")
+// Paths start with "key" or "value" followed by json path segments (value.items[0].id, value['a b'], value.items[*].id).
+// Supported operators: == != > >= < <= =~ (regex match), ! && || and parentheses. A path on its own checks that the field
+// exists and is not null or false. Comparisons with paths that match several values (wildcards) are true if any value matches.
+#[derive(Debug, Clone)]
+pub struct Query {
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Root {
+    Key,
+    Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Match,
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Path(Root, JsonPath),
+    Literal(Value),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    // chains of the same operator are kept flat, so long chains don't make the tree (and the evaluation) deep.
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Truthy(Operand),
+    Compare(Operand, Op, Operand),
+    Match(Operand, Regex),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Op(Op),
+    Literal(Value),
+    Path(String),
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Query, String> {
+        let tokens = tokenize(query)?;
+        let mut parser = Parser{tokens: tokens, pos: 0, depth: 0};
+        let expr = parser.parse_or()?;
+        if parser.pos < parser.tokens.len() {
+            return Err(format!("unexpected {:?} in query", parser.tokens[parser.pos]));
+        }
+        Ok(Query{expr: expr})
+    }
+
+    // the key and value are the decoded texts, which are parsed as json (or used as strings if they're not json).
+    pub fn matches(&self, key: &str, value: &str) -> bool {
+        let doc = Doc{key: to_json(key), value: to_json(value)};
+        eval(&self.expr, &doc)
+    }
+}
+
+fn to_json(text: &str) -> Value {
+    serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+}
+
+struct Doc {
+    key: Value,
+    value: Value,
+}
+
+fn eval(expr: &Expr, doc: &Doc) -> bool {
+    match expr {
+        Expr::And(exprs) => exprs.iter().all(|e| eval(e, doc)),
+        Expr::Or(exprs) => exprs.iter().any(|e| eval(e, doc)),
+        Expr::Not(inner) => !eval(inner, doc),
+        Expr::Truthy(operand) => values(operand, doc).iter().any(|v| !matches!(v, Value::Null | Value::Bool(false))),
+        Expr::Compare(left, op, right) => {
+            let right_values = values(right, doc);
+            values(left, doc).iter().any(|l| right_values.iter().any(|r| compare(l, *op, r)))
+        },
+        Expr::Match(operand, regex) => values(operand, doc).iter().any(|v| match v {
+            Value::String(s) => regex.is_match(s),
+            other => regex.is_match(&other.to_string()),
+        }),
+    }
+}
+
+fn values(operand: &Operand, doc: &Doc) -> Vec<Value> {
+    match operand {
+        Operand::Literal(value) => vec![value.clone()],
+        Operand::Path(root, path) => {
+            let mut out = vec![];
+            let source = if *root == Root::Key { &doc.key } else { &doc.value };
+            path.for_each(source, &mut |v| out.push(v.clone()));
+            out
+        },
+    }
+}
+
+fn compare(left: &Value, op: Op, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(l), Value::Number(r)) => l.as_f64().unwrap_or(f64::NAN).partial_cmp(&r.as_f64().unwrap_or(f64::NAN)),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        (Value::Bool(l), Value::Bool(r)) => Some(l.cmp(r)),
+        (Value::Null, Value::Null) => Some(std::cmp::Ordering::Equal),
+        _ => None,
+    };
+    match (op, ordering) {
+        (Op::Eq, Some(o)) => o.is_eq(),
+        (Op::Eq, None) => left == right,
+        (Op::Ne, Some(o)) => o.is_ne(),
+        (Op::Ne, None) => left != right,
+        (Op::Gt, Some(o)) => o.is_gt(),
+        (Op::Ge, Some(o)) => o.is_ge(),
+        (Op::Lt, Some(o)) => o.is_lt(),
+        (Op::Le, Some(o)) => o.is_le(),
+        _ => false,
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            ' ' | '\t' | '\n' | '\r' => i += 1,
+            '(' => { tokens.push(Token::LParen); i += 1; },
+            ')' => { tokens.push(Token::RParen); i += 1; },
+            '&' if next == Some('&') => { tokens.push(Token::And); i += 2; },
+            '|' if next == Some('|') => { tokens.push(Token::Or); i += 2; },
+            '=' if next == Some('=') => { tokens.push(Token::Op(Op::Eq)); i += 2; },
+            '=' if next == Some('~') => { tokens.push(Token::Op(Op::Match)); i += 2; },
+            '!' if next == Some('=') => { tokens.push(Token::Op(Op::Ne)); i += 2; },
+            '!' => { tokens.push(Token::Not); i += 1; },
+            '>' if next == Some('=') => { tokens.push(Token::Op(Op::Ge)); i += 2; },
+            '>' => { tokens.push(Token::Op(Op::Gt)); i += 1; },
+            '<' if next == Some('=') => { tokens.push(Token::Op(Op::Le)); i += 2; },
+            '<' => { tokens.push(Token::Op(Op::Lt)); i += 1; },
+            '"' | '\'' => {
+                let mut text = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => return Err(format!("unterminated string in query at position {}", i)),
+                        Some('\\') => {
+                            match chars.get(j + 1) {
+                                None => return Err(format!("unterminated string in query at position {}", i)),
+                                Some(escaped) => text.push(*escaped),
+                            }
+                            j += 2;
+                        },
+                        Some(q) if *q == c => break,
+                        Some(other) => {
+                            text.push(*other);
+                            j += 1;
+                        },
+                    }
+                }
+                tokens.push(Token::Literal(Value::String(text)));
+                i = j + 1;
+            },
+            c if c.is_ascii_digit() || (c == '-' && next.map_or(false, |n| n.is_ascii_digit())) => {
+                let end = chars[i + 1..].iter().position(|c| !(c.is_ascii_digit() || *c == '.' || *c == 'e' || *c == 'E')).map(|p| p + i + 1).unwrap_or(chars.len());
+                let text: String = chars[i..end].iter().collect();
+                let number: Value = serde_json::from_str(&text).map_err(|_| format!("bad number {} in query", text))?;
+                tokens.push(Token::Literal(number));
+                i = end;
+            },
+            c if c.is_alphabetic() || c == '_' => {
+                // a word, possibly followed by path segments (brackets can contain anything, including spaces).
+                let mut j = i;
+                while j < chars.len() {
+                    match chars[j] {
+                        '[' => {
+                            j = chars[j..].iter().position(|c| *c == ']').map(|p| p + j + 1).ok_or(format!("missing ']' in query at position {}", j))?;
+                        },
+                        c if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '*' || c == '$' => j += 1,
+                        _ => break,
+                    }
+                }
+                let word: String = chars[i..j].iter().collect();
+                tokens.push(match word.as_str() {
+                    "true" => Token::Literal(Value::Bool(true)),
+                    "false" => Token::Literal(Value::Bool(false)),
+                    "null" => Token::Literal(Value::Null),
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Path(word),
+                });
+                i = j;
+            },
+            _ => return Err(format!("unexpected '{}' in query at position {}", c, i)),
+        }
+    }
+    Ok(tokens)
+}
+
+// the maximum nesting of parentheses and negations, deeper queries could overflow the stack when parsed or evaluated.
+const MAX_DEPTH: usize = 64;
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut exprs = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            exprs.push(self.parse_and()?);
+        }
+        Ok(if exprs.len() == 1 { exprs.pop().unwrap() } else { Expr::Or(exprs) })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut exprs = vec![self.parse_unary()?];
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            exprs.push(self.parse_unary()?);
+        }
+        Ok(if exprs.len() == 1 { exprs.pop().unwrap() } else { Expr::And(exprs) })
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Not) | Some(Token::LParen) => {
+                if self.depth >= MAX_DEPTH {
+                    return Err("query is nested too deeply".to_string());
+                }
+                self.depth += 1;
+                let expr = self.parse_nested();
+                self.depth -= 1;
+                expr
+            },
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_nested(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            _ => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => Err("missing ')' in query".to_string()),
+                }
+            },
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, String> {
+        let left = self.parse_operand()?;
+        let op = match self.peek() {
+            Some(Token::Op(op)) => *op,
+            _ => return Ok(Expr::Truthy(left)),
+        };
+        self.pos += 1;
+        if op == Op::Match {
+            return match self.next() {
+                Some(Token::Literal(Value::String(pattern))) => {
+                    let regex = Regex::new(&pattern).map_err(|e| format!("bad regex {} in query: {}", pattern, e))?;
+                    Ok(Expr::Match(left, regex))
+                },
+                _ => Err("=~ must be followed by a regex string".to_string()),
+            };
+        }
+        let right = self.parse_operand()?;
+        Ok(Expr::Compare(left, op, right))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        match self.next() {
+            Some(Token::Literal(value)) => Ok(Operand::Literal(value)),
+            Some(Token::Path(path)) => parse_path(&path),
+            Some(token) => Err(format!("unexpected {:?} in query", token)),
+            None => Err("unexpected end of query".to_string()),
+        }
+    }
+}
+
+fn parse_path(path: &str) -> Result<Operand, String> {
+    let (root, rest) = if let Some(rest) = path.strip_prefix("value") {
+        (Root::Value, rest)
+    } else if let Some(rest) = path.strip_prefix("key") {
+        (Root::Key, rest)
+    } else {
+        return Err(format!("paths in queries must start with \"key\" or \"value\", found: {}", path));
+    };
+    if !rest.is_empty() && !rest.starts_with('.') && !rest.starts_with('[') {
+        return Err(format!("paths in queries must start with \"key\" or \"value\", found: {}", path));
+    }
+    Ok(Operand::Path(root, JsonPath::parse(rest)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(query: &str, key: &str, value: &str) -> bool {
+        Query::parse(query).unwrap().matches(key, value)
+    }
+
+    #[test]
+    fn tokenizes_operators_literals_and_paths() {
+        let tokens = tokenize(r#"!(value.a['b c'][0] >= -1.5) && key =~ "^x" || value.s != 'it\'s' and not null"#).unwrap();
+        assert_eq!(tokens, vec![
+            Token::Not,
+            Token::LParen,
+            Token::Path("value.a['b c'][0]".to_string()),
+            Token::Op(Op::Ge),
+            Token::Literal(serde_json::json!(-1.5)),
+            Token::RParen,
+            Token::And,
+            Token::Path("key".to_string()),
+            Token::Op(Op::Match),
+            Token::Literal(Value::String("^x".to_string())),
+            Token::Or,
+            Token::Path("value.s".to_string()),
+            Token::Op(Op::Ne),
+            Token::Literal(Value::String("it's".to_string())),
+            Token::And,
+            Token::Not,
+            Token::Literal(Value::Null),
+        ]);
+        assert!(tokenize(r#"value.a == "open"#).is_err());
+        assert!(tokenize("value.a # 1").is_err());
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let value = r#"{"a": 1, "b": 2}"#;
+        assert!(matches("value.a == 1 || value.a == 5 && value.b == 5", "", value));
+        assert!(!matches("(value.a == 1 || value.a == 5) && value.b == 5", "", value));
+        assert!(matches("!value.c && value.b > 1", "", value));
+        assert!(!matches("not (value.a == 1 or value.b == 1)", "", value));
+    }
+
+    #[test]
+    fn compares_wildcards_existentially() {
+        let value = r#"{"items": [{"id": 1, "tags": ["a"]}, {"id": 7, "tags": ["b", "c"]}]}"#;
+        assert!(matches("value.items[*].id == 7", "", value));
+        assert!(!matches("value.items[*].id > 7", "", value));
+        assert!(matches("value.items[*].tags[*] == 'c'", "", value));
+        assert!(matches("value.items.*.id < 2", "", value));
+        assert!(!matches("value.missing[*].id", "", value));
+    }
+
+    #[test]
+    fn matches_regexes() {
+        let value = r#"{"email": "Jane@Example.com", "n": 42}"#;
+        assert!(matches(r#"value.email =~ "(?i)@example\\.com$""#, "", value));
+        assert!(!matches(r#"value.email =~ "^john""#, "", value));
+        assert!(matches(r#"value.n =~ "^4""#, "", value));
+        assert!(matches(r#"key =~ "^vip-""#, "vip-1", value));
+        assert!(Query::parse("value.email =~ 5").is_err());
+        assert!(Query::parse(r#"value.email =~ "(""#).is_err());
+    }
+
+    #[test]
+    fn rejects_deep_nesting() {
+        assert!(Query::parse(&format!("{}value.a", "!".repeat(MAX_DEPTH))).is_ok());
+        assert_eq!(Query::parse(&format!("{}value.a", "!".repeat(100000))).unwrap_err(), "query is nested too deeply");
+        assert_eq!(Query::parse(&format!("{}value.a{}", "(".repeat(100000), ")".repeat(100000))).unwrap_err(), "query is nested too deeply");
+        let chain = vec!["value.a == 1"; 100000].join(" && ");
+        assert!(matches(&chain, "", r#"{"a": 1}"#));
+    }
+}
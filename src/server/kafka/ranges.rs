@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use rdkafka::Message;
+use rdkafka::consumer::Consumer;
+use rdkafka::message::OwnedMessage;
+use rdkafka::TopicPartitionList;
+use tokio::time::timeout;
+use tracing::{error, warn};
+
+use crate::common::errors::{map_error, retry, ApiError};
+use crate::kafka::api::{self, LoggingConsumer};
+
+// reading ends if no messages arrive for this long (and the consumer doesn't move past the ends of the ranges).
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// how often the consumer's positions are checked while no messages arrive.
+const POSITION_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+// The offsets to read (inclusive) from a partition.
+pub struct PartitionRange {
+    pub partition: i32,
+    pub from: i64,
+    pub to: i64,
+}
+
+// parses a comma-separated list of partitions.
+pub fn parse_partitions(partitions: &str) -> Result<Vec<i32>, ApiError> {
+    partitions.split(",")
+        .map(|p| p.trim().parse::<i32>().map_err(|_| ApiError::BadRequest(format!("bad partition: {}", p))))
+        .collect()
+}
+
+// the offsets to read for each of the requested partitions (or all partitions), partitions with nothing to read are skipped.
+pub fn partition_ranges(
+    topic: &str,
+    partitions: Option<Vec<i32>>,
+    from_offset: Option<i64>,
+    to_offset: Option<i64>,
+    from_time: Option<i64>,
+    to_time: Option<i64>) -> Result<Vec<PartitionRange>, ApiError> {
+    let mut offsets = api::get_topic_offsets(topic)?;
+    if let Some(wanted) = partitions {
+        for partition in &wanted {
+            if !offsets.iter().any(|o| o.partition == *partition) {
+                return Err(ApiError::BadRequest(format!("topic {} has no partition {}", topic, partition)));
+            }
+        }
+        offsets.retain(|o| wanted.contains(&o.partition));
+    }
+    let from_times = match from_time {
+        None => None,
+        Some(time) => Some(api::get_offsets_for_timestamp(topic, &offsets, time)?),
+    };
+    // the last offset before to_time is the one right before the first offset after it.
+    let to_times = match to_time {
+        None => None,
+        Some(time) => Some(api::get_offsets_for_timestamp(topic, &offsets, time + 1)?),
+    };
+    let mut ranges = Vec::with_capacity(offsets.len());
+    for offset in offsets {
+        let mut from = offset.low.max(from_offset.unwrap_or(0));
+        let mut to = offset.high - 1;
+        if let Some(to_offset) = to_offset {
+            to = to.min(to_offset);
+        }
+        if let Some(from_time) = from_times.as_ref().and_then(|t| t.get(&offset.partition)) {
+            from = from.max(*from_time);
+        }
+        if let Some(to_time) = to_times.as_ref().and_then(|t| t.get(&offset.partition)) {
+            to = to.min(*to_time - 1);
+        }
+        if from > to {
+            continue;
+        }
+        ranges.push(PartitionRange{partition: offset.partition, from: from, to: to});
+    }
+    Ok(ranges)
+}
+
+// Reads the messages of the given ranges of a topic (from all of the partitions together), without keeping them in memory.
+pub struct RangeReader {
+    topic: String,
+    consumer: LoggingConsumer,
+    remaining: Vec<PartitionRange>,
+    total: i64,
+}
+
+impl RangeReader {
+    pub fn new(topic: &str, ranges: Vec<PartitionRange>) -> Result<RangeReader, ApiError> {
+        let mut reader = RangeReader{
+            topic: topic.to_string(),
+            consumer: api::stream_consumer()?,
+            total: 0,
+            remaining: vec![],
+        };
+        reader.reset(ranges)?;
+        Ok(reader)
+    }
+
+    // starts reading other ranges of the topic (instead of the remaining ones), reusing the consumer.
+    pub fn reset(&mut self, ranges: Vec<PartitionRange>) -> Result<(), ApiError> {
+        let mut assignment = TopicPartitionList::new();
+        for range in &ranges {
+            map_error(assignment.add_partition_offset(&self.topic, range.partition, rdkafka::Offset::Offset(range.from)))?;
+        }
+        retry("assigning consumer", &mut || self.consumer.assign(&assignment)).map_err(ApiError::KafkaUnavailable)?;
+        self.total = ranges.iter().map(|r| r.to - r.from + 1).sum();
+        self.remaining = ranges;
+        Ok(())
+    }
+
+    // the number of offsets in the ranges (compacted topics and transaction markers can make the actual number of messages smaller).
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+
+    // whether all of the ranges were read (next returns None also when no messages arrived for a while).
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining.is_empty()
+    }
+
+    // the next message in the ranges, or None when all of the ranges were read (or reading stopped for being idle).
+    pub async fn next(&mut self) -> Option<OwnedMessage> {
+        let mut idle = Duration::ZERO;
+        while !self.remaining.is_empty() {
+            let message = match timeout(POSITION_CHECK_INTERVAL, self.consumer.recv()).await {
+                Err(_) => {
+                    self.remove_passed_ranges();
+                    idle += POSITION_CHECK_INTERVAL;
+                    if !self.remaining.is_empty() && idle >= IDLE_TIMEOUT {
+                        warn!("no messages arrived for {:?} while reading topic {}, stopping", IDLE_TIMEOUT, self.topic);
+                        return None;
+                    }
+                    continue;
+                },
+                Ok(Err(e)) => {
+                    error!("Kafka error: {}", e);
+                    continue;
+                },
+                Ok(Ok(m)) => m.detach(),
+            };
+            idle = Duration::ZERO;
+            let range = match self.remaining.iter().position(|r| r.partition == message.partition()) {
+                None => continue,
+                Some(index) => index,
+            };
+            let (from, to) = (self.remaining[range].from, self.remaining[range].to);
+            if message.offset() < from {
+                continue;
+            }
+            if message.offset() >= to {
+                self.remaining.swap_remove(range);
+            }
+            if message.offset() > to {
+                continue;
+            }
+            return Some(message);
+        }
+        None
+    }
+
+    // the ends of the ranges are often not messages (i.e transaction commit markers), which the consumer skips without
+    // returning anything, so ranges whose end the consumer's position has passed are done.
+    fn remove_passed_ranges(&mut self) {
+        let positions = match self.consumer.position() {
+            Ok(positions) => positions,
+            Err(e) => {
+                warn!("failed getting the consumer positions of topic {}: {}", self.topic, e);
+                return;
+            },
+        };
+        let topic = &self.topic;
+        self.remaining.retain(|range| match positions.find_partition(topic, range.partition).map(|p| p.offset()) {
+            Some(rdkafka::Offset::Offset(position)) => position <= range.to,
+            _ => true,
+        });
+    }
+}
@@ -0,0 +1,43 @@
+use regex::Regex;
+
+use crate::common::errors::ApiError;
+use crate::kafka::dto;
+use crate::kafka::query::Query;
+
+// A search over the decoded (and masked) messages, compiled once per request.
+#[derive(Clone)]
+pub struct Search {
+    pattern: String,
+    style: dto::SearchStyle,
+    regex: Option<Regex>,
+    query: Option<Query>,
+}
+
+impl Search {
+    // returns None when there is nothing to search for.
+    pub fn new(search: Option<&str>, style: dto::SearchStyle) -> Result<Option<Search>, ApiError> {
+        let pattern = match search {
+            None => return Ok(None),
+            Some(pattern) => pattern,
+        };
+        let regex = match style {
+            dto::SearchStyle::Regex => Some(Regex::new(pattern).map_err(|e| ApiError::BadRequest(format!("bad search regex: {}", e)))?),
+            _ => None,
+        };
+        let query = match style {
+            dto::SearchStyle::Query => Some(Query::parse(pattern).map_err(|e| ApiError::BadRequest(format!("bad search query: {}", e)))?),
+            _ => None,
+        };
+        Ok(Some(Search{pattern: pattern.to_string(), style: style, regex: regex, query: query}))
+    }
+
+    pub fn matches(&self, key: &str, value: &str) -> bool {
+        let text = || format!("{},{}", key, value);
+        match self.style {
+            dto::SearchStyle::None => text().to_ascii_lowercase().contains(&self.pattern.to_ascii_lowercase()),
+            dto::SearchStyle::CaseSensitive => text().contains(&self.pattern),
+            dto::SearchStyle::Regex => self.regex.as_ref().unwrap().is_match(&text()),
+            dto::SearchStyle::Query => self.query.as_ref().unwrap().matches(key, value),
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use futures::stream::{self, TryStreamExt};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rocket::serde::json::Json;
+use serverapi::Decoder;
+use tracing::{error, info, warn};
+use utoipa::OpenApi;
+
+use crate::config;
+use crate::auth::guard::User;
+use crate::common::errors::{ApiError, ErrorResult};
+use crate::common::jobs::{Job, Jobs, JobStatus};
+use crate::kafka::api;
+use crate::kafka::dto;
+use crate::kafka::masking::Masker;
+use crate::kafka::ranges::{partition_ranges, PartitionRange, RangeReader};
+use crate::kafka::search::Search;
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+#[derive(Clone, Default)]
+struct SearchState {
+    progress: dto::SearchProgress,
+    messages: Vec<dto::TopicMessage>,
+}
+
+static SEARCH_JOBS: Lazy<Jobs<SearchState>> = Lazy::new(Jobs::new);
+
+#[utoipa::path(
+    tag = "kafka",
+    request_body = dto::SearchJobRequest,
+    responses(
+        (status = 200, body = dto::SearchJobResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[post("/api/search", data = "<request>")]
+pub async fn start_search(request: Json<dto::SearchJobRequest>, user: User) -> Result<Json<dto::SearchJobResult>, ApiError> {
+    let request = request.into_inner();
+    let search = match Search::new(Some(request.search.as_str()).filter(|s| !s.is_empty()), request.search_style.unwrap_or(dto::SearchStyle::None))? {
+        None => return Err(ApiError::BadRequest("search is required".to_string())),
+        Some(search) => search,
+    };
+    let topics = resolve_topics(&request, &user)?;
+    if topics.is_empty() {
+        return Err(ApiError::BadRequest("no topics to search".to_string()));
+    }
+
+    let mut targets = Vec::with_capacity(topics.len());
+    let mut units = vec![];
+    for topic in &topics {
+        let (key_decoders, value_decoders) = api::message_decoders(topic, request.decoding.as_deref().unwrap_or(""))?;
+        for range in partition_ranges(topic, None, None, None, request.from_time, request.to_time)? {
+            units.push((targets.len(), range));
+        }
+        targets.push(TopicSearch{
+            topic: topic.to_string(),
+            key_decoders: key_decoders,
+            value_decoders: value_decoders,
+            masker: Masker::new(topic, &user),
+        });
+    }
+    let max_results = (*config::SETTINGS).jobs.search_max_results;
+    let progress = dto::SearchProgress{
+        topics: topics,
+        partitions: units.len(),
+        total: units.iter().map(|(_, r)| r.to - r.from + 1).sum(),
+        ..Default::default()
+    };
+
+    let job = SEARCH_JOBS.start(&user, SearchState{progress: progress, messages: vec![]})?;
+    info!("user {} started searching {} partitions of {} topics (job {})", user.name, units.len(), targets.len(), job.id);
+    let run = SearchRun{
+        job: Arc::clone(&job),
+        search: search,
+        targets: targets,
+        max_results: request.max_results.unwrap_or(max_results).min(max_results),
+        full: AtomicBool::new(false),
+    };
+    tokio::spawn(async move {
+        let result = run.run(units).await;
+        if let Err(e) = &result {
+            error!("search job {} failed: {}", run.job.id, e);
+        }
+        run.job.finish(result);
+    });
+
+    Ok(Json(job_result(&job, 0, DEFAULT_PAGE_SIZE)))
+}
+
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::SearchJobResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/search/<id>?<offset>&<limit>")]
+pub fn get_search(id: &str, offset: Option<usize>, limit: Option<usize>, user: User) -> Result<Json<dto::SearchJobResult>, ApiError> {
+    let job = SEARCH_JOBS.get(id, &user)?;
+    Ok(Json(job_result(&job, offset.unwrap_or(0), limit.unwrap_or(DEFAULT_PAGE_SIZE))))
+}
+
+#[utoipa::path(
+    tag = "kafka",
+    responses(
+        (status = 200, body = dto::SearchJobResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[delete("/api/search/<id>")]
+pub fn cancel_search(id: &str, user: User) -> Result<Json<dto::SearchJobResult>, ApiError> {
+    let job = SEARCH_JOBS.get(id, &user)?;
+    job.cancel();
+    Ok(Json(job_result(&job, 0, DEFAULT_PAGE_SIZE)))
+}
+
+fn job_result(job: &Job<SearchState>, offset: usize, limit: usize) -> dto::SearchJobResult {
+    let (status, (progress, messages), failure) = job.view(|state| {
+        (state.progress.clone(), state.messages.iter().skip(offset).take(limit).cloned().collect())
+    });
+    dto::SearchJobResult{
+        id: job.id.to_string(),
+        status: status,
+        failure: failure,
+        progress: progress,
+        offset: offset,
+        messages: messages,
+    }
+}
+
+// the requested topics, together with the topics matching the regex (the whole name has to match).
+// Requested topics the user can't access fail the search, while such topics matching the regex are skipped.
+fn resolve_topics(request: &dto::SearchJobRequest, user: &User) -> Result<Vec<String>, ApiError> {
+    let mut topics = Vec::with_capacity(request.topics.len());
+    for topic in &request.topics {
+        api::check_topic_access(user, topic)?;
+        api::get_topic_partitions(topic)?;
+        if !topics.contains(topic) {
+            topics.push(topic.to_string());
+        }
+    }
+    if let Some(pattern) = &request.topic_regex {
+        let regex = Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| ApiError::BadRequest(format!("bad topic regex: {}", e)))?;
+        for topic in api::get_topic_names()? {
+            if regex.is_match(&topic) && user.can_read_topic(&topic) && !topics.contains(&topic) {
+                topics.push(topic);
+            }
+        }
+    }
+    Ok(topics)
+}
+
+struct TopicSearch {
+    topic: String,
+    key_decoders: Vec<&'static Box<dyn Decoder>>,
+    value_decoders: Vec<&'static Box<dyn Decoder>>,
+    masker: Masker,
+}
+
+struct SearchRun {
+    job: Arc<Job<SearchState>>,
+    search: Search,
+    targets: Vec<TopicSearch>,
+    max_results: usize,
+    full: AtomicBool,
+}
+
+impl SearchRun {
+    // searches the partitions in parallel (each with its own consumer), up to the configured concurrency.
+    async fn run(&self, units: Vec<(usize, PartitionRange)>) -> Result<(), ApiError> {
+        let concurrency = (*config::SETTINGS).jobs.search_concurrency.max(1);
+        stream::iter(units.into_iter().map(Ok))
+            .try_for_each_concurrent(concurrency, |(target, range)| self.search_partition(&self.targets[target], range))
+            .await
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.job.is_cancelled() || self.full.load(Ordering::Relaxed)
+    }
+
+    async fn search_partition(&self, target: &TopicSearch, range: PartitionRange) -> Result<(), ApiError> {
+        if self.is_stopped() {
+            return Ok(());
+        }
+        let partition = range.partition;
+        let mut reader = RangeReader::new(&target.topic, vec![range])?;
+        while !self.is_stopped() {
+            let message = match reader.next().await {
+                None => break,
+                Some(message) => message,
+            };
+            let parsed = api::parse_message(message, partition, Some(&self.search), false, &target.key_decoders, &target.value_decoders, &target.masker).await?;
+            self.job.update(|state| {
+                state.progress.scanned += 1;
+                let message = match parsed {
+                    None => return,
+                    Some(message) => message,
+                };
+                // other partitions might still find matches after the search is full.
+                if state.messages.len() >= self.max_results {
+                    return;
+                }
+                state.progress.matched += 1;
+                state.messages.push(message);
+                if state.messages.len() >= self.max_results {
+                    state.progress.truncated = true;
+                    self.full.store(true, Ordering::Relaxed);
+                }
+            });
+        }
+        // the rest of the partition wasn't searched, so it isn't counted as completed.
+        if !reader.is_exhausted() && !self.is_stopped() {
+            warn!("search job {} stopped reading topic {} partition {} after being idle", self.job.id, target.topic, partition);
+            self.job.update(|state| state.progress.idle_partitions.push(format!("{}/{}", target.topic, partition)));
+            return Ok(());
+        }
+        self.job.update(|state| state.progress.completed_partitions += 1);
+        Ok(())
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        start_search,
+        get_search,
+        cancel_search,
+    ),
+    components(schemas(
+        dto::SearchJobRequest,
+        dto::SearchProgress,
+        dto::SearchJobResult,
+        dto::TopicMessage,
+        JobStatus,
+    )),
+)]
+pub struct ApiDoc;
@@ -1,41 +1,622 @@
 use rocket::serde::json::Json;
-use std::collections::HashMap;
+use utoipa::OpenApi;
+use std::collections::{BTreeMap, HashMap};
+
+use regex::Regex;
+use tracing::warn;
 
 use crate::config;
+use crate::auth::guard::User;
+use crate::auth::roles::Action;
+use crate::kafka;
 use crate::kafka_connect::dto;
-use crate::common::errors::{map_error, retry_async};
+use crate::common::errors::{map_error, retry_async, ApiError, ErrorResult};
 
-#[get("/api/kafka-connect/connectors")]
-pub async fn get_connectors() -> Result<Json<dto::GetConnectorsResult>, String> {
-    let resp = retry_async("fetching connectors", || reqwest::get(format!("{}/connectors", (*config::SETTINGS).kafka_connect.url))).await?;
-    let data = map_error(resp.json::<Vec<String>>().await)?;
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = dto::GetConnectorsResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/kafka-connect/connectors?<cluster>")]
+pub async fn get_connectors(cluster: Option<&str>, _user: User) -> Result<Json<dto::GetConnectorsResult>, ApiError> {
+    let base_url = cluster_url(cluster)?;
+    let resp = retry_async("fetching connectors", || reqwest::get(format!("{}/connectors", base_url))).await.map_err(ApiError::Connect)?;
+    let data = map_error(check_status(resp).await?.json::<Vec<String>>().await)?;
     Ok(Json(dto::GetConnectorsResult{connectors: data}))
 }
 
-#[get("/api/kafka-connect/connector/<connector>/status")]
-pub async fn get_connector_status(connector: &str) -> Result<Json<dto::GetConnectorStatusResult>, String> {
-    let resp = retry_async("fetching connector status", || reqwest::get(format!("{}/connectors/{}/status", (*config::SETTINGS).kafka_connect.url, connector))).await?;
-    let data = map_error(resp.json::<dto::GetConnectorStatusResult>().await)?;
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = dto::GetClustersResult),
+    ),
+)]
+#[get("/api/kafka-connect/clusters")]
+pub fn get_clusters(_user: User) -> Json<dto::GetClustersResult> {
+    let clusters = (*config::SETTINGS).kafka_connect.get_clusters().into_iter().map(|c| c.name).collect();
+    Json(dto::GetClustersResult{clusters: clusters})
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = dto::GetAllConnectorsResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/kafka-connect/connectors/all")]
+pub async fn get_all_connectors(_user: User) -> Result<Json<dto::GetAllConnectorsResult>, ApiError> {
+    // the clusters are fetched together, and an unreachable cluster is reported without failing the others.
+    let clusters = (*config::SETTINGS).kafka_connect.get_clusters();
+    let results = futures::future::join_all(clusters.iter().map(|cluster| fetch_connectors(&cluster.url))).await;
+    let mut connectors = vec![];
+    let mut errors = vec![];
+    for (cluster, result) in clusters.iter().zip(results) {
+        match result {
+            Ok(names) => {
+                for name in names {
+                    connectors.push(dto::ClusterConnector{cluster: cluster.name.to_string(), name: name});
+                }
+            },
+            Err(e) => {
+                warn!("failed fetching the connectors of kafka-connect cluster {}: {}", cluster.name, e);
+                errors.push(dto::ClusterError{cluster: cluster.name.to_string(), code: e.code(), message: e.message().to_string()});
+            },
+        }
+    }
+    Ok(Json(dto::GetAllConnectorsResult{connectors: connectors, errors: errors}))
+}
+
+async fn fetch_connectors(base_url: &str) -> Result<Vec<String>, ApiError> {
+    let resp = retry_async("fetching connectors", || reqwest::get(format!("{}/connectors", base_url))).await.map_err(ApiError::Connect)?;
+    Ok(map_error(check_status(resp).await?.json::<Vec<String>>().await)?)
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = dto::GetConnectorStatusResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/kafka-connect/connector/<connector>/status?<cluster>")]
+pub async fn get_connector_status(connector: &str, cluster: Option<&str>, _user: User) -> Result<Json<dto::GetConnectorStatusResult>, ApiError> {
+    let base_url = cluster_url(cluster)?;
+    let resp = retry_async("fetching connector status", || reqwest::get(format!("{}/connectors/{}/status", base_url, connector))).await.map_err(ApiError::Connect)?;
+    let data = map_error(check_status(resp).await?.json::<dto::GetConnectorStatusResult>().await)?;
     Ok(Json(data))
 }
 
-#[get("/api/kafka-connect/connector/<connector>/config")]
-pub async fn get_connector_config(connector: &str) -> Result<Json<dto::GetConnectorConfigResult>, String> {
-    let resp = retry_async("fetching connector config", || reqwest::get(format!("{}/connectors/{}/config", (*config::SETTINGS).kafka_connect.url, connector))).await?;
-    let data = map_error(resp.json::<HashMap<String, String>>().await)?;
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = dto::GetConnectorConfigResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/kafka-connect/connector/<connector>/config?<cluster>")]
+pub async fn get_connector_config(connector: &str, cluster: Option<&str>, _user: User) -> Result<Json<dto::GetConnectorConfigResult>, ApiError> {
+    let base_url = cluster_url(cluster)?;
+    let resp = retry_async("fetching connector config", || reqwest::get(format!("{}/connectors/{}/config", base_url, connector))).await.map_err(ApiError::Connect)?;
+    let data = map_error(check_status(resp).await?.json::<HashMap<String, String>>().await)?;
     Ok(Json(dto::GetConnectorConfigResult{config: data}))
 }
 
-#[get("/api/kafka-connect/connector/<connector>/tasks")]
-pub async fn get_connector_tasks(connector: &str) -> Result<Json<Vec<dto::GetConnectorTasksResult>>, String> {
-    let resp = retry_async("fetching connector tasks", || reqwest::get(format!("{}/connectors/{}/tasks", (*config::SETTINGS).kafka_connect.url, connector))).await?;
-    let data = map_error(resp.json::<Vec<dto::GetConnectorTasksResult>>().await)?;
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = Vec<dto::GetConnectorTasksResult>),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/kafka-connect/connector/<connector>/tasks?<cluster>")]
+pub async fn get_connector_tasks(connector: &str, cluster: Option<&str>, _user: User) -> Result<Json<Vec<dto::GetConnectorTasksResult>>, ApiError> {
+    let base_url = cluster_url(cluster)?;
+    let resp = retry_async("fetching connector tasks", || reqwest::get(format!("{}/connectors/{}/tasks", base_url, connector))).await.map_err(ApiError::Connect)?;
+    let data = map_error(check_status(resp).await?.json::<Vec<dto::GetConnectorTasksResult>>().await)?;
     Ok(Json(data))
 }
 
-#[get("/api/kafka-connect/connector/<connector>/tasks/<task>/status")]
-pub async fn get_connector_task_status(connector: &str, task: &str) -> Result<Json<dto::GetConnectorTaskStatusResult>, String> {
-    let resp = retry_async("fetching connector task status", || reqwest::get(format!("{}/connectors/{}/tasks/{}/status", (*config::SETTINGS).kafka_connect.url, connector, task))).await?;
-    let data = map_error(resp.json::<dto::ConnectorTask>().await)?;
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = dto::GetConnectorTaskStatusResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/kafka-connect/connector/<connector>/tasks/<task>/status?<cluster>")]
+pub async fn get_connector_task_status(connector: &str, task: &str, cluster: Option<&str>, _user: User) -> Result<Json<dto::GetConnectorTaskStatusResult>, ApiError> {
+    let base_url = cluster_url(cluster)?;
+    let resp = retry_async("fetching connector task status", || reqwest::get(format!("{}/connectors/{}/tasks/{}/status", base_url, connector, task))).await.map_err(ApiError::Connect)?;
+    let data = map_error(check_status(resp).await?.json::<dto::ConnectorTask>().await)?;
     Ok(Json(dto::GetConnectorTaskStatusResult{task: data}))
-}
\ No newline at end of file
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = dto::GetConnectHealthResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/kafka-connect/health?<cluster>")]
+pub async fn get_connect_health(cluster: Option<&str>, _user: User) -> Result<Json<dto::GetConnectHealthResult>, ApiError> {
+    let base_url = cluster_url(cluster)?;
+    let resp = retry_async("fetching connectors health", || reqwest::get(format!("{}/connectors?expand=status&expand=info", base_url))).await.map_err(ApiError::Connect)?;
+    let data = map_error(check_status(resp).await?.json::<HashMap<String, dto::ExpandedConnector>>().await)?;
+
+    let mut connectors = dto::StateCounts::default();
+    let mut tasks = dto::StateCounts::default();
+    let mut workers: BTreeMap<String, dto::WorkerHealth> = BTreeMap::new();
+    let mut failures = vec![];
+    for (name, expanded) in data {
+        let status = expanded.status;
+        connectors.add(&status.connector.state);
+        workers.entry(status.connector.worker_id.to_string())
+            .or_insert_with(|| dto::WorkerHealth{worker_id: status.connector.worker_id.to_string(), ..Default::default()})
+            .connectors.add(&status.connector.state);
+        if status.connector.state == "FAILED" {
+            failures.push(dto::ConnectorFailure{
+                connector: name.to_string(),
+                task: None,
+                worker_id: status.connector.worker_id.to_string(),
+                trace: status.connector.trace,
+            });
+        }
+        for task in status.tasks {
+            tasks.add(&task.state);
+            workers.entry(task.worker_id.to_string())
+                .or_insert_with(|| dto::WorkerHealth{worker_id: task.worker_id.to_string(), ..Default::default()})
+                .tasks.add(&task.state);
+            if task.state == "FAILED" {
+                failures.push(dto::ConnectorFailure{
+                    connector: name.to_string(),
+                    task: Some(task.id),
+                    worker_id: task.worker_id,
+                    trace: task.trace,
+                });
+            }
+        }
+    }
+
+    Ok(Json(dto::GetConnectHealthResult{
+        connectors: connectors,
+        tasks: tasks,
+        workers: workers.into_values().collect(),
+        failures: failures,
+    }))
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = dto::GetLineageResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/kafka-connect/lineage?<cluster>")]
+pub async fn get_lineage(cluster: Option<&str>, user: User) -> Result<Json<dto::GetLineageResult>, ApiError> {
+    let base_url = cluster_url(cluster)?;
+    let resp = retry_async("fetching connectors info", || reqwest::get(format!("{}/connectors?expand=info", base_url))).await.map_err(ApiError::Connect)?;
+    let data = map_error(check_status(resp).await?.json::<BTreeMap<String, HashMap<String, dto::ConnectorInfo>>>().await)?;
+    let mut connectors = Vec::with_capacity(data.len());
+    for (_, mut expanded) in data {
+        if let Some(info) = expanded.remove("info") {
+            let mut lineage = _get_connector_lineage(&base_url, info).await?;
+            lineage.topics.retain(|t| user.can_read_topic(&t.topic));
+            connectors.push(lineage);
+        }
+    }
+    Ok(Json(dto::GetLineageResult{connectors: connectors}))
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = dto::ConnectorLineage),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/kafka-connect/connector/<connector>/topics?<cluster>")]
+pub async fn get_connector_topics(connector: &str, cluster: Option<&str>, user: User) -> Result<Json<dto::ConnectorLineage>, ApiError> {
+    let base_url = cluster_url(cluster)?;
+    let resp = retry_async("fetching connector info", || reqwest::get(format!("{}/connectors/{}", base_url, connector))).await.map_err(ApiError::Connect)?;
+    let info = map_error(check_status(resp).await?.json::<dto::ConnectorInfo>().await)?;
+    let mut data = _get_connector_lineage(&base_url, info).await?;
+    data.topics.retain(|t| user.can_read_topic(&t.topic));
+    Ok(Json(data))
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = dto::GetConnectorPluginsResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[get("/api/kafka-connect/connector-plugins?<cluster>")]
+pub async fn get_connector_plugins(cluster: Option<&str>, _user: User) -> Result<Json<dto::GetConnectorPluginsResult>, ApiError> {
+    let base_url = cluster_url(cluster)?;
+    let resp = retry_async("fetching connector plugins", || reqwest::get(format!("{}/connector-plugins", base_url))).await.map_err(ApiError::Connect)?;
+    let data = map_error(check_status(resp).await?.json::<Vec<dto::ConnectorPlugin>>().await)?;
+    Ok(Json(dto::GetConnectorPluginsResult{plugins: data}))
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    request_body = HashMap<String, String>,
+    responses(
+        (status = 200, body = dto::ValidateConnectorConfigResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[put("/api/kafka-connect/connector-plugins/<plugin>/config/validate?<cluster>", data = "<connector_config>")]
+pub async fn validate_connector_config(plugin: &str, connector_config: Json<HashMap<String, String>>, cluster: Option<&str>, _user: User) -> Result<Json<dto::ValidateConnectorConfigResult>, ApiError> {
+    let base_url = cluster_url(cluster)?;
+    let mut connector_config = connector_config.into_inner();
+    connector_config.entry("connector.class".to_string()).or_insert_with(|| plugin.to_string());
+    let data = _validate_config(&base_url, plugin, &connector_config).await?;
+    Ok(Json(data))
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    request_body = dto::CreateConnectorRequest,
+    responses(
+        (status = 200, body = dto::ConnectorInfo),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[post("/api/kafka-connect/connectors?<cluster>", data = "<request>")]
+pub async fn create_connector(request: Json<dto::CreateConnectorRequest>, cluster: Option<&str>, user: User) -> Result<Json<dto::ConnectorInfo>, ApiError> {
+    check_write_access(&user)?;
+    let base_url = cluster_url(cluster)?;
+    let client = reqwest::Client::new();
+    let url = format!("{}/connectors", base_url);
+    let body = request.into_inner();
+    let mut validated_config = body.config.clone();
+    validated_config.entry("name".to_string()).or_insert_with(|| body.name.to_string());
+    _ensure_valid_config(&base_url, &validated_config).await?;
+    // actions which change the connectors are sent once, retrying them could apply them twice (or fail after they were applied).
+    let resp = client.post(&url).json(&body).send().await.map_err(|e| ApiError::Connect(e.to_string()))?;
+    let data = map_error(check_status(resp).await?.json::<dto::ConnectorInfo>().await)?;
+    Ok(Json(data))
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    request_body = HashMap<String, String>,
+    responses(
+        (status = 200, body = dto::ConnectorInfo),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[put("/api/kafka-connect/connector/<connector>/config?<cluster>", data = "<connector_config>")]
+pub async fn update_connector_config(connector: &str, connector_config: Json<HashMap<String, String>>, cluster: Option<&str>, user: User) -> Result<Json<dto::ConnectorInfo>, ApiError> {
+    check_write_access(&user)?;
+    let base_url = cluster_url(cluster)?;
+    let client = reqwest::Client::new();
+    let url = format!("{}/connectors/{}/config", base_url, connector);
+    let mut body = connector_config.into_inner();
+    body.entry("name".to_string()).or_insert_with(|| connector.to_string());
+    _ensure_valid_config(&base_url, &body).await?;
+    let resp = retry_async("updating connector config", || client.put(&url).json(&body).send()).await.map_err(ApiError::Connect)?;
+    let data = map_error(check_status(resp).await?.json::<dto::ConnectorInfo>().await)?;
+    Ok(Json(data))
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, description = "Done"),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[put("/api/kafka-connect/connector/<connector>/pause?<cluster>")]
+pub async fn pause_connector(connector: &str, cluster: Option<&str>, user: User) -> Result<(), ApiError> {
+    check_write_access(&user)?;
+    let base_url = cluster_url(cluster)?;
+    let client = reqwest::Client::new();
+    let url = format!("{}/connectors/{}/pause", base_url, connector);
+    let resp = client.put(&url).send().await.map_err(|e| ApiError::Connect(e.to_string()))?;
+    check_status(resp).await?;
+    Ok(())
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, description = "Done"),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[put("/api/kafka-connect/connector/<connector>/resume?<cluster>")]
+pub async fn resume_connector(connector: &str, cluster: Option<&str>, user: User) -> Result<(), ApiError> {
+    check_write_access(&user)?;
+    let base_url = cluster_url(cluster)?;
+    let client = reqwest::Client::new();
+    let url = format!("{}/connectors/{}/resume", base_url, connector);
+    let resp = client.put(&url).send().await.map_err(|e| ApiError::Connect(e.to_string()))?;
+    check_status(resp).await?;
+    Ok(())
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, body = dto::RestartConnectorResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[post("/api/kafka-connect/connector/<connector>/restart?<include_tasks>&<only_failed>&<cluster>")]
+pub async fn restart_connector(connector: &str, include_tasks: Option<bool>, only_failed: Option<bool>, cluster: Option<&str>, user: User) -> Result<Json<dto::RestartConnectorResult>, ApiError> {
+    check_write_access(&user)?;
+    let base_url = cluster_url(cluster)?;
+    let client = reqwest::Client::new();
+    let url = format!("{}/connectors/{}/restart?includeTasks={}&onlyFailed={}",
+        base_url, connector, include_tasks.unwrap_or(false), only_failed.unwrap_or(false));
+    let resp = client.post(&url).send().await.map_err(|e| ApiError::Connect(e.to_string()))?;
+    let resp = check_status(resp).await?;
+    // kafka-connect only returns the new status when asked to restart tasks as well (with 202 Accepted), otherwise it returns 204 No Content.
+    if resp.status() != reqwest::StatusCode::ACCEPTED {
+        return Ok(Json(dto::RestartConnectorResult{status: None}));
+    }
+    let data = map_error(check_status(resp).await?.json::<dto::GetConnectorStatusResult>().await)?;
+    Ok(Json(dto::RestartConnectorResult{status: Some(data)}))
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, description = "Done"),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[delete("/api/kafka-connect/connector/<connector>?<cluster>")]
+pub async fn delete_connector(connector: &str, cluster: Option<&str>, user: User) -> Result<(), ApiError> {
+    check_write_access(&user)?;
+    let base_url = cluster_url(cluster)?;
+    let client = reqwest::Client::new();
+    let url = format!("{}/connectors/{}", base_url, connector);
+    let resp = client.delete(&url).send().await.map_err(|e| ApiError::Connect(e.to_string()))?;
+    check_status(resp).await?;
+    Ok(())
+}
+
+#[utoipa::path(
+    tag = "kafka-connect",
+    responses(
+        (status = 200, description = "Done"),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
+#[post("/api/kafka-connect/connector/<connector>/tasks/<task>/restart?<cluster>")]
+pub async fn restart_connector_task(connector: &str, task: &str, cluster: Option<&str>, user: User) -> Result<(), ApiError> {
+    check_write_access(&user)?;
+    let base_url = cluster_url(cluster)?;
+    let client = reqwest::Client::new();
+    let url = format!("{}/connectors/{}/tasks/{}/restart", base_url, connector, task);
+    let resp = client.post(&url).send().await.map_err(|e| ApiError::Connect(e.to_string()))?;
+    check_status(resp).await?;
+    Ok(())
+}
+
+async fn _validate_config(base_url: &str, plugin: &str, connector_config: &HashMap<String, String>) -> Result<dto::ValidateConnectorConfigResult, ApiError> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/connector-plugins/{}/config/validate", base_url, plugin);
+    let resp = retry_async("validating connector config", || client.put(&url).json(connector_config).send()).await.map_err(ApiError::Connect)?;
+    map_error(check_status(resp).await?.json::<dto::ValidateConnectorConfigResult>().await)
+}
+
+// validates the config against the connector's plugin before it is sent to kafka-connect, so that all of the invalid fields are reported together.
+async fn _ensure_valid_config(base_url: &str, connector_config: &HashMap<String, String>) -> Result<(), ApiError> {
+    let plugin = connector_config.get("connector.class").ok_or(ApiError::BadRequest("connector.class is missing from the config".to_string()))?;
+    let validation = _validate_config(base_url, plugin, connector_config).await?;
+    if validation.error_count == 0 {
+        return Ok(());
+    }
+    let errors = validation.configs.iter()
+        .filter(|c| !c.value.errors.is_empty())
+        .map(|c| format!("{}: {}", c.value.name, c.value.errors.join(", ")))
+        .collect::<Vec<String>>()
+        .join("; ");
+    Err(ApiError::BadRequest(format!("invalid connector config ({} errors): {}", validation.error_count, errors)))
+}
+
+// combines the topics reported by kafka-connect as active for the connector, the topics from the connector's config,
+// and the topics assigned to the connector's consumer group (for sink connectors).
+async fn _get_connector_lineage(base_url: &str, info: dto::ConnectorInfo) -> Result<dto::ConnectorLineage, ApiError> {
+    let mut topics: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut add_topic = |topic: &str, source: &str| {
+        let sources = topics.entry(topic.to_string()).or_insert_with(Vec::new);
+        if !sources.iter().any(|s| s == source) {
+            sources.push(source.to_string());
+        }
+    };
+
+    for topic in _get_active_topics(base_url, &info.name).await {
+        add_topic(&topic, "active");
+    }
+    if let Some(configured) = info.config.get("topics") {
+        for topic in configured.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            add_topic(topic, "config");
+        }
+    }
+    if let Some(pattern) = info.config.get("topics.regex") {
+        // kafka-connect matches the whole topic name against the regex
+        let regex = map_error(Regex::new(&format!("^(?:{})$", pattern)))?;
+        for topic in kafka::api::get_topic_names()? {
+            if regex.is_match(&topic) {
+                add_topic(&topic, "config");
+            }
+        }
+    }
+
+    let is_sink = info.r#type.as_deref() == Some("sink");
+    let group_name = match info.config.get("consumer.override.group.id") {
+        Some(group) => group.to_string(),
+        None => format!("connect-{}", info.name),
+    };
+    let group_topics = kafka::api::get_group_topics(&group_name)?;
+    if let Some(assigned) = &group_topics {
+        for topic in assigned {
+            add_topic(topic, "consumer-group");
+        }
+    }
+
+    let mut out = Vec::with_capacity(topics.len());
+    for (topic, sources) in topics {
+        let mut lag = None;
+        if is_sink {
+            match kafka::api::get_group_topic_offsets(&group_name, &topic) {
+                Ok(offsets) => {
+                    lag = Some(offsets.iter().map(|o| dto::ConnectorTopicLag{
+                        partition: o.partition_offsets.partition,
+                        committed_offset: o.offset,
+                        high: o.partition_offsets.high,
+                        lag: o.partition_offsets.high - o.offset,
+                    }).collect::<Vec<dto::ConnectorTopicLag>>());
+                },
+                Err(err) => warn!("failed fetching lag of group {} for topic {}: {}", group_name, topic, err),
+            }
+        }
+        let total_lag = lag.as_ref().map(|partitions| partitions.iter().map(|p| p.lag).sum());
+        out.push(dto::ConnectorTopic{
+            topic: topic,
+            sources: sources,
+            lag: lag,
+            total_lag: total_lag,
+        });
+    }
+
+    Ok(dto::ConnectorLineage{
+        connector: info.name,
+        r#type: info.r#type,
+        consumer_group: if is_sink || group_topics.is_some() { Some(group_name) } else { None },
+        topics: out,
+    })
+}
+
+// the active topics api can be disabled in kafka-connect (topic.tracking.enable=false), in which case we rely on the other sources.
+async fn _get_active_topics(base_url: &str, connector: &str) -> Vec<String> {
+    let resp = match retry_async("fetching connector active topics", || reqwest::get(format!("{}/connectors/{}/topics", base_url, connector))).await {
+        Ok(resp) => resp,
+        Err(err) => {
+            warn!("failed fetching active topics for connector {}: {}", connector, err);
+            return vec![];
+        }
+    };
+    let resp = match check_status(resp).await {
+        Ok(resp) => resp,
+        Err(err) => {
+            warn!("failed fetching active topics for connector {}: {}", connector, err);
+            return vec![];
+        }
+    };
+    match resp.json::<HashMap<String, dto::ActiveTopics>>().await {
+        Ok(mut data) => data.remove(connector).map(|active| active.topics).unwrap_or_default(),
+        Err(err) => {
+            warn!("failed parsing active topics for connector {}: {}", connector, err);
+            vec![]
+        }
+    }
+}
+
+fn check_write_access(user: &User) -> Result<(), ApiError> {
+    if !user.can(Action::ConnectWrite) {
+        return Err(ApiError::Forbidden(format!("user {} is not allowed to perform {} actions", user.name, Action::ConnectWrite.as_str())));
+    }
+    Ok(())
+}
+
+// returns the url of the requested kafka-connect cluster, or of the first configured cluster if no cluster was requested.
+fn cluster_url(cluster: Option<&str>) -> Result<String, ApiError> {
+    let clusters = (*config::SETTINGS).kafka_connect.get_clusters();
+    let found = match cluster {
+        None => clusters.into_iter().next(),
+        Some(name) => clusters.into_iter().find(|c| c.name == name),
+    };
+    match found {
+        Some(c) => Ok(c.url),
+        None => Err(ApiError::BadRequest(format!("no kafka-connect cluster named {}", cluster.unwrap_or("")))),
+    }
+}
+
+async fn check_status(resp: reqwest::Response) -> Result<reqwest::Response, ApiError> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let message = match resp.json::<dto::ConnectError>().await {
+        Ok(err) => format!("kafka-connect returned {}: {}", status, err.message),
+        Err(_) => format!("kafka-connect returned {}", status),
+    };
+    match status {
+        reqwest::StatusCode::NOT_FOUND => Err(ApiError::NotFound(message)),
+        reqwest::StatusCode::BAD_REQUEST | reqwest::StatusCode::UNPROCESSABLE_ENTITY => Err(ApiError::BadRequest(message)),
+        _ => Err(ApiError::Connect(message)),
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_connectors,
+        get_clusters,
+        get_all_connectors,
+        get_connector_status,
+        get_connector_config,
+        get_connector_tasks,
+        get_connector_task_status,
+        get_connect_health,
+        get_lineage,
+        get_connector_topics,
+        get_connector_plugins,
+        validate_connector_config,
+        create_connector,
+        update_connector_config,
+        pause_connector,
+        resume_connector,
+        restart_connector,
+        delete_connector,
+        restart_connector_task,
+    ),
+    components(schemas(
+        dto::GetConnectorsResult,
+        dto::GetClustersResult,
+        dto::ClusterConnector,
+        dto::GetAllConnectorsResult,
+        dto::ClusterError,
+        dto::GetConnectorStatusResult,
+        dto::ConnectorStatus,
+        dto::GetConnectorConfigResult,
+        dto::ConnectorTaskID,
+        dto::GetConnectorTasksResult,
+        dto::GetConnectorTaskStatusResult,
+        dto::ConnectorTask,
+        dto::CreateConnectorRequest,
+        dto::ConnectorInfo,
+        dto::RestartConnectorResult,
+        dto::ConnectError,
+        dto::ConnectorPlugin,
+        dto::GetConnectorPluginsResult,
+        dto::ValidateConnectorConfigResult,
+        dto::ConfigInfo,
+        dto::ConfigDefinition,
+        dto::ConfigValue,
+        dto::ExpandedConnector,
+        dto::StateCounts,
+        dto::WorkerHealth,
+        dto::ConnectorFailure,
+        dto::GetConnectHealthResult,
+        dto::ActiveTopics,
+        dto::ConnectorTopicLag,
+        dto::ConnectorTopic,
+        dto::ConnectorLineage,
+        dto::GetLineageResult,
+        dto::ConnectorState,
+    )),
+)]
+pub struct ApiDoc;
@@ -1,12 +1,38 @@
 use rocket::serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
 use std::collections::HashMap;
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetConnectorsResult {
     pub connectors: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, ToSchema)]
+pub struct GetClustersResult {
+    pub clusters: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ClusterConnector {
+    pub cluster: String,
+    pub name: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ClusterError {
+    pub cluster: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GetAllConnectorsResult {
+    pub connectors: Vec<ClusterConnector>,
+    // the clusters whose connectors couldn't be listed.
+    pub errors: Vec<ClusterError>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct GetConnectorStatusResult {
     pub name: String,
     pub connector: ConnectorStatus,
@@ -14,42 +40,203 @@ pub struct GetConnectorStatusResult {
     pub r#type: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ConnectorStatus {
     pub state: String,
     pub worker_id: String,
+    pub trace: Option<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct GetConnectorConfigResult {
     pub config: HashMap<String, String>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ConnectorTaskID {
     pub connector: String,
     pub task: i64,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct GetConnectorTasksResult {
     pub id: ConnectorTaskID,
     pub config: HashMap<String, String>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct GetConnectorTaskStatusResult {
     pub task: ConnectorTask,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ConnectorTask {
     pub state: String,
     pub id: i64,
     pub worker_id: String,
+    pub trace: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateConnectorRequest {
+    pub name: String,
+    pub config: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ConnectorInfo {
+    pub name: String,
+    pub config: HashMap<String, String>,
+    pub tasks: Vec<ConnectorTaskID>,
+    pub r#type: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RestartConnectorResult {
+    pub status: Option<GetConnectorStatusResult>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ConnectError {
+    pub error_code: i64,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ConnectorPlugin {
+    pub class: String,
+    pub r#type: Option<String>,
+    pub version: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GetConnectorPluginsResult {
+    pub plugins: Vec<ConnectorPlugin>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ValidateConnectorConfigResult {
+    pub name: String,
+    pub error_count: i64,
+    pub groups: Vec<String>,
+    pub configs: Vec<ConfigInfo>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ConfigInfo {
+    pub definition: ConfigDefinition,
+    pub value: ConfigValue,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ConfigDefinition {
+    pub name: String,
+    pub r#type: String,
+    pub required: bool,
+    pub default_value: Option<String>,
+    pub importance: Option<String>,
+    pub documentation: Option<String>,
+    pub group: Option<String>,
+    pub width: Option<String>,
+    pub display_name: Option<String>,
+    pub dependents: Vec<String>,
+    pub order: i64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ConfigValue {
+    pub name: String,
+    pub value: Option<String>,
+    pub recommended_values: Vec<String>,
+    pub errors: Vec<String>,
+    pub visible: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ExpandedConnector {
+    pub status: GetConnectorStatusResult,
+    pub info: ConnectorInfo,
+}
+
+#[derive(Serialize, Default, ToSchema)]
+pub struct StateCounts {
+    pub running: i64,
+    pub paused: i64,
+    pub failed: i64,
+    pub unassigned: i64,
+    pub other: i64,
+}
+
+impl StateCounts {
+    pub fn add(&mut self, state: &str) {
+        match state {
+            "RUNNING" => self.running += 1,
+            "PAUSED" => self.paused += 1,
+            "FAILED" => self.failed += 1,
+            "UNASSIGNED" => self.unassigned += 1,
+            _ => self.other += 1,
+        }
+    }
+}
+
+#[derive(Serialize, Default, ToSchema)]
+pub struct WorkerHealth {
+    pub worker_id: String,
+    pub connectors: StateCounts,
+    pub tasks: StateCounts,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ConnectorFailure {
+    pub connector: String,
+    pub task: Option<i64>,
+    pub worker_id: String,
+    pub trace: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GetConnectHealthResult {
+    pub connectors: StateCounts,
+    pub tasks: StateCounts,
+    pub workers: Vec<WorkerHealth>,
+    pub failures: Vec<ConnectorFailure>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ActiveTopics {
+    pub topics: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ConnectorTopicLag {
+    pub partition: i32,
+    pub committed_offset: i64,
+    pub high: i64,
+    pub lag: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ConnectorTopic {
+    pub topic: String,
+    pub sources: Vec<String>,
+    pub lag: Option<Vec<ConnectorTopicLag>>,
+    pub total_lag: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ConnectorLineage {
+    pub connector: String,
+    pub r#type: Option<String>,
+    pub consumer_group: Option<String>,
+    pub topics: Vec<ConnectorTopic>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GetLineageResult {
+    pub connectors: Vec<ConnectorLineage>,
 }
 
-#[derive(Serialize, FromFormField, Debug)]
+#[derive(Serialize, FromFormField, Debug, ToSchema)]
 pub enum ConnectorState {
     Running,
     Failed,
@@ -7,7 +7,22 @@ use std::path::{Path, PathBuf};
 mod config;
 mod kafka {
     pub mod api;
+    pub mod masking;
+    pub mod export;
+    pub mod import;
+    pub mod copy;
+    pub mod search_job;
+    pub mod key_lookup;
+    pub mod compacted;
+    pub mod message;
+    mod search;
+    mod query;
+    mod projection;
+    mod ranges;
+    mod produce;
+    mod partitioners;
     mod dto;
+    mod json_path;
     mod decoders {
         pub mod avro;
         pub mod utf8;
@@ -26,10 +41,32 @@ mod schema_registry {
 }
 mod common {
     pub mod errors;
+    pub mod capabilities;
+    pub mod metrics;
+    pub mod logging;
+    pub mod health;
+    pub mod openapi;
+    pub mod jobs;
+}
+mod audit {
+    pub mod log;
+    mod sinks;
+}
+mod auth {
+    pub mod api;
+    pub mod guard;
+    pub mod roles;
+    mod dto;
+    mod oidc;
+}
+
+#[get("/health")]
+fn health() -> &'static str {
+    "OK"
 }
 
 #[get("/<_file..>")]
-async fn index(_file: PathBuf) -> Option<NamedFile> {
+async fn index(_file: PathBuf, _user: auth::guard::User) -> Option<NamedFile> {
     let page_directory_path = public();
     NamedFile::open(Path::new(&page_directory_path).join("index.html")).await.ok()
 }
@@ -40,6 +77,12 @@ async fn files(file: PathBuf) -> Option<NamedFile> {
     NamedFile::open(Path::new(&page_directory_path).join(file)).await.ok()
 }
 
+// the swagger ui of the api docs, served from the installed npm package.
+#[get("/docs/assets/<file..>")]
+async fn docs_files(file: PathBuf) -> Option<NamedFile> {
+    let page_directory_path = swagger_ui();
+    NamedFile::open(Path::new(&page_directory_path).join(file)).await.ok()
+}
 
 fn public() -> &'static str {
     relative!("../../public")
@@ -49,16 +92,40 @@ fn assets() -> &'static str {
     relative!("../../dist/client")
 }
 
+fn swagger_ui() -> &'static str {
+    relative!("../../node_modules/swagger-ui-dist")
+}
+
 #[launch]
 fn rocket() -> _ {
+    // prints the openapi specification (i.e for generating the client types) without starting the server.
+    if std::env::args().any(|arg| arg == "--print-openapi") {
+        println!("{}", common::openapi::openapi().to_pretty_json().unwrap());
+        std::process::exit(0);
+    }
+    common::logging::init();
+    auth::guard::check_config().unwrap();
+    auth::roles::check_config().unwrap();
+    kafka::masking::check_config().unwrap();
+    audit::log::start().unwrap();
     kafka::api::update_cache_thread();
 
     let figment = rocket::Config::figment()
         .merge(("port", (*config::SETTINGS).server.port));
 
-    rocket::custom(figment).mount("/", routes![
+    let capabilities = common::capabilities::capabilities();
+    let mut app = rocket::custom(figment).mount("/", common::logging::traced(routes![
         index,
         files,
+        docs_files,
+        health,
+        common::health::get_liveness,
+        common::health::get_readiness,
+        common::metrics::get_metrics,
+        auth::api::get_me,
+        common::capabilities::get_capabilities,
+        common::openapi::get_openapi,
+        common::openapi::get_docs,
         kafka::api::get_topics,
         kafka::api::get_offsets,
         kafka::api::get_messages,
@@ -71,13 +138,61 @@ fn rocket() -> _ {
         kafka::api::get_group_members,
         kafka::api::get_offset_for_timestamp,
         kafka::api::get_decoders,
+        kafka::export::get_export,
+        kafka::search_job::start_search,
+        kafka::search_job::get_search,
+        kafka::search_job::cancel_search,
+        kafka::key_lookup::get_key_versions,
+        kafka::compacted::get_compacted_view,
+        kafka::message::get_message,
+        kafka_connect::api::get_clusters,
         kafka_connect::api::get_connectors,
+        kafka_connect::api::get_all_connectors,
         kafka_connect::api::get_connector_status,
         kafka_connect::api::get_connector_config,
         kafka_connect::api::get_connector_tasks,
         kafka_connect::api::get_connector_task_status,
+        kafka_connect::api::get_connect_health,
+        kafka_connect::api::get_lineage,
+        kafka_connect::api::get_connector_topics,
+        kafka_connect::api::get_connector_plugins,
+        kafka_connect::api::validate_connector_config,
         schema_registry::api::get_subjects,
         schema_registry::api::get_subject_versions,
         schema_registry::api::get_schema,
-    ])
+    ]))
+    .register("/", catchers![auth::api::unauthorized])
+    .attach(audit::log::Auditor)
+    .attach(common::metrics::RequestTimer);
+
+    if (*config::SETTINGS).auth.mode == "oidc" {
+        app = app.mount("/", common::logging::traced(routes![
+            auth::api::login,
+            auth::api::callback,
+            auth::api::logout,
+        ]));
+    }
+
+    if capabilities.produce {
+        app = app.mount("/", common::logging::traced(routes![
+            kafka::import::import_messages,
+            kafka::copy::start_copy,
+            kafka::copy::get_copy,
+            kafka::copy::cancel_copy,
+        ]));
+    }
+
+    if capabilities.kafka_connect {
+        app = app.mount("/", common::logging::traced(routes![
+            kafka_connect::api::create_connector,
+            kafka_connect::api::update_connector_config,
+            kafka_connect::api::pause_connector,
+            kafka_connect::api::resume_connector,
+            kafka_connect::api::restart_connector,
+            kafka_connect::api::delete_connector,
+            kafka_connect::api::restart_connector_task,
+        ]));
+    }
+
+    app
 }
\ No newline at end of file
@@ -1,26 +1,80 @@
 use rocket::serde::json::Json;
+use utoipa::OpenApi;
 
 use crate::config;
+use crate::auth::guard::User;
 use crate::schema_registry::dto;
-use crate::common::errors::{map_error, retry_async};
+use crate::common::errors::{map_error, retry_async, ApiError, ErrorResult};
 
+#[utoipa::path(
+    tag = "schema-registry",
+    responses(
+        (status = 200, body = dto::GetSubjectsResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/schema-registry/subjects")]
-pub async fn get_subjects() -> Result<Json<dto::GetSubjectsResult>, String> {
-    let resp = retry_async("fetching subjects", || reqwest::get(format!("{}/subjects", (*config::SETTINGS).confluent_schema_registry.url))).await?;
-    let data = map_error(resp.json::<Vec<String>>().await)?;
+pub async fn get_subjects(_user: User) -> Result<Json<dto::GetSubjectsResult>, ApiError> {
+    let resp = retry_async("fetching subjects", || reqwest::get(format!("{}/subjects", (*config::SETTINGS).confluent_schema_registry.url))).await.map_err(ApiError::Registry)?;
+    let data = map_error(check_status(resp).await?.json::<Vec<String>>().await)?;
     Ok(Json(dto::GetSubjectsResult{subjects: data}))
 }
 
+#[utoipa::path(
+    tag = "schema-registry",
+    responses(
+        (status = 200, body = dto::GetSubjectVersionsResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/schema-registry/versions/<subject>")]
-pub async fn get_subject_versions(subject: &str) -> Result<Json<dto::GetSubjectVersionsResult>, String> {
-    let resp = retry_async("fetching schema versions", || reqwest::get(format!("{}/subjects/{}/versions", (*config::SETTINGS).confluent_schema_registry.url, subject))).await?;
-    let data = map_error(resp.json::<Vec<i64>>().await)?;
+pub async fn get_subject_versions(subject: &str, _user: User) -> Result<Json<dto::GetSubjectVersionsResult>, ApiError> {
+    let resp = retry_async("fetching schema versions", || reqwest::get(format!("{}/subjects/{}/versions", (*config::SETTINGS).confluent_schema_registry.url, subject))).await.map_err(ApiError::Registry)?;
+    let data = map_error(check_status(resp).await?.json::<Vec<i64>>().await)?;
     Ok(Json(dto::GetSubjectVersionsResult{versions: data}))
 }
 
+#[utoipa::path(
+    tag = "schema-registry",
+    responses(
+        (status = 200, body = dto::GetSchemaResult),
+        (status = "default", description = "Error", body = ErrorResult),
+    ),
+)]
 #[get("/api/schema-registry/schema/<subject>/<version>")]
-pub async fn get_schema(subject: &str, version: i64) -> Result<Json<dto::GetSchemaResult>, String> {
-    let resp = retry_async("fetching schema", || reqwest::get(format!("{}/subjects/{}/versions/{}", (*config::SETTINGS).confluent_schema_registry.url, subject, version))).await?;
-    let data = map_error(resp.json::<dto::GetSchemaResult>().await)?;
+pub async fn get_schema(subject: &str, version: i64, _user: User) -> Result<Json<dto::GetSchemaResult>, ApiError> {
+    let resp = retry_async("fetching schema", || reqwest::get(format!("{}/subjects/{}/versions/{}", (*config::SETTINGS).confluent_schema_registry.url, subject, version))).await.map_err(ApiError::Registry)?;
+    let data = map_error(check_status(resp).await?.json::<dto::GetSchemaResult>().await)?;
     Ok(Json(data))
 }
+
+async fn check_status(resp: reqwest::Response) -> Result<reqwest::Response, ApiError> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let message = match resp.json::<dto::RegistryError>().await {
+        Ok(err) => format!("schema registry returned {}: {}", status, err.message),
+        Err(_) => format!("schema registry returned {}", status),
+    };
+    match status {
+        reqwest::StatusCode::NOT_FOUND => Err(ApiError::NotFound(message)),
+        reqwest::StatusCode::UNPROCESSABLE_ENTITY => Err(ApiError::BadRequest(message)),
+        _ => Err(ApiError::Registry(message)),
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_subjects,
+        get_subject_versions,
+        get_schema,
+    ),
+    components(schemas(
+        dto::GetSubjectsResult,
+        dto::GetSubjectVersionsResult,
+        dto::GetSchemaResult,
+    )),
+)]
+pub struct ApiDoc;
@@ -1,19 +1,26 @@
 use rocket::serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetSubjectsResult {
     pub subjects: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct GetSubjectVersionsResult {
     pub versions: Vec<i64>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct GetSchemaResult {
     pub subject: String,
     pub id: i64,
     pub version: i64,
     pub schema: String,
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize)]
+pub struct RegistryError {
+    pub error_code: i32,
+    pub message: String,
+}
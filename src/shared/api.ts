@@ -1,6 +1,9 @@
 import { Schema } from "avsc";
 
-export type MaybeError = { error?: string }
+export type MaybeError = { error?: string, code?: string, retryable?: boolean }
+
+// the search style in json request bodies, where the ui's empty style (a plain search) is "none".
+export type RequestSearchStyle = `none` | `case-sensitive` | `regex` | `query`
 
 export type PartitionMetadata = { error_description?: string, partition_id: number, leader: number, replicas: number[], isr: number[] }
 export type TopicMetadata = { name: string, partitions: PartitionMetadata[] }
@@ -9,7 +12,26 @@ export type TopicsOffsets = Array<TopicOffsets>
 export type ConsumerOffsets = Array<{ metadata: string | null, offset: number, partition_offsets?: TopicOffsets }>
 export type TopicConsumerGroups = Array<{group_id: string, offsets: ConsumerOffsets}>
 export type TopicMessage = { topic: string, partition: number, value: string, key: string, timestamp: number, offset: number, key_decoding: string, value_decoding: string }
-export type TopicMessages = { messages: TopicMessage[], has_timeout: boolean }
+export type TopicMessages = { messages: TopicMessage[], has_timeout: boolean, scanned?: number, last_offset?: number | null }
+export type ExportFormat = `ndjson` | `csv` | `avro`
+export type ImportPartitioning = `preserve` | `remap` | `partitioner`
+export type ImportTimestamps = `keep` | `now`
+export type ImportResult = MaybeError & { produced: number, skipped: number }
+export type JobStatus = `Running` | `Completed` | `Cancelled` | `Failed`
+export type CopyRequest = { source_topic: string, destination_topic: string, partitions?: number[], from_offset?: number, to_offset?: number, from_time?: number, to_time?: number, search?: string, search_style?: RequestSearchStyle, decoding?: string, header_filters?: { [key: string]: string }, set_headers?: { [key: string]: string }, remove_headers?: string[], keep_partitions?: boolean, keep_timestamps?: boolean, commit_group?: string, dry_run?: boolean }
+export type CopyProgress = { dry_run: boolean, total: number, scanned: number, matched: number, produced: number }
+export type CopyJobResult = MaybeError & { id: string, status: JobStatus, failure?: string, progress: CopyProgress }
+export type SearchJobRequest = { topics?: string[], topic_regex?: string, from_time?: number, to_time?: number, search: string, search_style?: RequestSearchStyle, decoding?: string, max_results?: number }
+export type SearchProgress = { topics: string[], partitions: number, completed_partitions: number, total: number, scanned: number, matched: number, truncated: boolean, idle_partitions: string[] }
+export type SearchJobResult = MaybeError & { id: string, status: JobStatus, failure?: string, progress: SearchProgress, offset: number, messages: TopicMessage[] }
+export type Partitioner = `murmur2` | `crc32` | `consistent-random`
+export type KeyLookupResult = MaybeError & { partitions: number[], messages: TopicMessage[], scanned: number, has_timeout: boolean }
+export type CompactedViewResult = MaybeError & { entries: TopicMessage[], keys: number, total: number, offset: number, last_offset?: number | null, truncated: boolean, has_timeout: boolean }
+export type TimestampType = `NotAvailable` | `CreateTime` | `LogAppendTime`
+export type RawFormat = `base64` | `hex`
+export type MessageHeader = { key: string, value: string, raw?: string | null }
+export type MessageDecoding = { attribute: `key` | `value`, decoder_id: string, decoder: string, result?: string | null, error?: string | null }
+export type GetMessageResult = MaybeError & { message: TopicMessage, timestamp?: number | null, timestamp_type: TimestampType, headers: MessageHeader[], raw_format: RawFormat, masked: boolean, raw_key?: string | null, raw_value?: string | null, key_size?: number | null, value_size?: number | null, size: number, decodings: MessageDecoding[] }
 export type Broker = { id: number; host: string; port: number }
 export type ConfigEntry = { name: string, value?: string, source: string, is_read_only: boolean, is_default: boolean, is_sensitive: boolean}
 export type GroupMemberMetadata = { member_id: string, client_id: string, client_host: string, metadata: string, assignment: string}
@@ -29,15 +51,37 @@ export type GetGroupsResult = MaybeError & { groups: GroupMetadata[] }
 export type GetGroupMembersResult = MaybeError & { members: GroupMemberMetadata[] }
 export type GetDecodersResult = MaybeError & { decoders: DecoderMetadata[] }
 
+export type User = { name: string, groups: string[] }
+export type GetMeResult = MaybeError & { user: User, mode: string, roles: string[] }
+
 export type GetSubjectsResult = MaybeError & { subjects: string[] }
 export type GetSubjectVersionsResult = MaybeError & { versions: number[] }
 export type GetSchemaResult = MaybeError & { subject: string, id: number, schema: string, version: number }
 
 export type ConnectorState = `RUNNING` | `FAILED` | `PAUSED`
 export type ConnectorConfig = { [key: string]: string }
-export type ConnectorTask = { state: ConnectorState, id: number, worker_id: string }
+export type ConnectorTask = { state: ConnectorState, id: number, worker_id: string, trace?: string }
 export type GetConnectorsResult = MaybeError & { connectors: string[] }
-export type GetConnectorStatusResult = MaybeError & { name: string, connector: {state: ConnectorState, worker_id: string}, tasks: ConnectorTask[], type: string }
+export type GetConnectorStatusResult = MaybeError & { name: string, connector: {state: ConnectorState, worker_id: string, trace?: string}, tasks: ConnectorTask[], type: string }
 export type GetConnectorConfigResult = MaybeError & { config: ConnectorConfig }
 export type GetConnectorTasksResult = MaybeError & { id: {connector: string, task: number}, config: ConnectorConfig }[]
-export type GetConnectorTaskStatusResult = MaybeError & { task: ConnectorTask }
\ No newline at end of file
+export type GetConnectorTaskStatusResult = MaybeError & { task: ConnectorTask }
+export type ConnectorInfo = MaybeError & { name: string, config: ConnectorConfig, tasks: {connector: string, task: number}[], type?: string }
+export type CreateConnectorRequest = { name: string, config: ConnectorConfig }
+export type RestartConnectorResult = MaybeError & { status?: GetConnectorStatusResult }
+export type ConnectorPlugin = { class: string, type?: string, version?: string }
+export type GetConnectorPluginsResult = MaybeError & { plugins: ConnectorPlugin[] }
+export type ConfigDefinition = { name: string, type: string, required: boolean, default_value?: string, importance?: string, documentation?: string, group?: string, width?: string, display_name?: string, dependents: string[], order: number }
+export type ConfigValue = { name: string, value?: string, recommended_values: string[], errors: string[], visible: boolean }
+export type ValidateConnectorConfigResult = MaybeError & { name: string, error_count: number, groups: string[], configs: { definition: ConfigDefinition, value: ConfigValue }[] }
+export type StateCounts = { running: number, paused: number, failed: number, unassigned: number, other: number }
+export type WorkerHealth = { worker_id: string, connectors: StateCounts, tasks: StateCounts }
+export type ConnectorFailure = { connector: string, task?: number, worker_id: string, trace?: string }
+export type GetConnectHealthResult = MaybeError & { connectors: StateCounts, tasks: StateCounts, workers: WorkerHealth[], failures: ConnectorFailure[] }
+export type GetClustersResult = MaybeError & { clusters: string[] }
+export type GetAllConnectorsResult = MaybeError & { connectors: { cluster: string, name: string }[], errors: { cluster: string, code: string, message: string }[] }
+export type ConnectorTopicLag = { partition: number, committed_offset: number, high: number, lag: number }
+export type ConnectorTopic = { topic: string, sources: string[], lag?: ConnectorTopicLag[], total_lag?: number }
+export type ConnectorLineage = MaybeError & { connector: string, type?: string, consumer_group?: string, topics: ConnectorTopic[] }
+export type GetLineageResult = MaybeError & { connectors: ConnectorLineage[] }
+export type Capabilities = { read_only: boolean, produce: boolean, topic_admin: boolean, group_admin: boolean, schema_registry: boolean, kafka_connect: boolean }
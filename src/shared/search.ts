@@ -1,4 +1,4 @@
-export type SearchStyle = `case-sensitive` | `regex` | ``
+export type SearchStyle = `case-sensitive` | `regex` | `query` | ``
 
 export function Includes(text: string, pattern: string, searchStyle: SearchStyle): boolean {
     switch (searchStyle) {
@@ -11,6 +11,9 @@ export function Includes(text: string, pattern: string, searchStyle: SearchStyle
             } catch (SyntaxError) {
                 return false
             }
+        case `query`:
+            // structured queries are evaluated by the server
+            return true
         default:
             return text.toLowerCase().includes(pattern.toLowerCase())
     }